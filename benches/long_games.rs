@@ -0,0 +1,48 @@
+//! Speed of the strategies summarizing the opponent in 10k turns games, against a majority
+//! strategy scanning the whole history at each turn
+//!
+//! Run with `cargo bench --bench long_games`.
+
+use std::{hint::black_box, time::Instant};
+
+use prisoner::{play_match, Choice, CustomFactory, PlayerFactory, Strategy, Turn, Weights};
+use rand::{rngs::SmallRng, RngCore, SeedableRng};
+
+const MATCHES: usize = 20;
+const TURNS: usize = 10_000;
+
+/// Soft majority, counting the collaborations of the opponent anew at each turn
+struct RescanningMajority;
+impl Strategy for RescanningMajority {
+    fn play(&mut self, turn: Turn<'_>, _rng: &mut dyn RngCore) -> Choice {
+        let collabs = turn.them.iter().filter(|c| **c == Choice::Collab).count();
+        (2 * collabs >= turn.them.len()).into()
+    }
+}
+
+fn main() {
+    let weights = Weights::default();
+    let opponent = PlayerFactory::Random(0.5);
+    let rescanning = PlayerFactory::Custom(CustomFactory::new(
+        "RescanningMajority",
+        "Soft majority, scanning the whole history at each turn",
+        |_, _| Box::new(RescanningMajority),
+    ));
+    let mut rng = SmallRng::seed_from_u64(0);
+    for player in [
+        PlayerFactory::Mean,
+        PlayerFactory::SoftMajority,
+        PlayerFactory::HardMajority,
+        rescanning,
+    ] {
+        let start = Instant::now();
+        for _ in 0..MATCHES {
+            black_box(play_match(&player, &opponent, &weights, TURNS, &mut rng));
+        }
+        println!(
+            "{:<20} {:>8.3} ms/match",
+            player.name().trim_end(),
+            1e3 * start.elapsed().as_secs_f64() / MATCHES as f64
+        );
+    }
+}
@@ -0,0 +1,77 @@
+//! Allocations and speed of the matches of a pool, against matches played one by one
+//!
+//! Run with `cargo bench --bench pool`.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    hint::black_box,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Instant,
+};
+
+use prisoner::{EloPoolBuilder, PlayerFactory, Rules, Weights};
+use rand::{rngs::SmallRng, SeedableRng};
+
+/// The system allocator, counting the allocations
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+const MATCHES: usize = 100_000;
+const TURNS: usize = 150;
+
+fn report(name: &str, run: impl FnOnce()) {
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let start = Instant::now();
+    run();
+    let elapsed = start.elapsed().as_secs_f64();
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - allocations;
+    println!(
+        "{name:<12} {:>6.2} allocations/match {:>10.0} matches/s",
+        allocations as f64 / MATCHES as f64,
+        MATCHES as f64 / elapsed
+    );
+}
+
+fn main() {
+    let rules = Rules {
+        perception_noise: 0.05,
+        ..Rules::default()
+    };
+    let weights = Weights::default();
+    let (p1, p2) = (PlayerFactory::TitForTat, PlayerFactory::Pavlov);
+
+    let mut rng = SmallRng::seed_from_u64(0);
+    report("one by one", || {
+        for _ in 0..MATCHES {
+            black_box(rules.play_match(&p1, &p2, &weights, TURNS, &mut rng));
+        }
+    });
+
+    let mut pool = EloPoolBuilder::new()
+        .roster([p1, p2])
+        .weights(weights)
+        .turns(TURNS, TURNS)
+        .rules(rules)
+        .build()
+        .expect("The settings are valid");
+    report("pool", || {
+        for _ in 0..MATCHES {
+            pool.play(&mut rng);
+        }
+    });
+}
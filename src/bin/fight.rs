@@ -1,88 +1,492 @@
-#![feature(never_type)]
-
 use std::{
     cmp::Reverse,
+    fs::{self, File, OpenOptions},
+    io::{self, BufRead, BufReader, Write},
+    path::{Path, PathBuf},
     str::FromStr,
-    time::{Duration, Instant},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
-use anyhow::Context;
-use clap::Parser;
+use anyhow::{bail, Context};
+use clap::{Parser, ValueEnum};
 use lazy_regex::regex_captures;
-use prisoner::{EloPool, EloPoolConfig, Weights};
-use rand::{rngs::SmallRng, SeedableRng};
+use prisoner::{
+    EloPool, EloPoolConfig, ExpectedModel, KSchedule, Matchmaking, ParsePlayerError, PlayerFactory,
+    Weights,
+};
+use rand::{distributions::Uniform, rngs::SmallRng, SeedableRng};
+use serde::Serialize;
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
 struct Args {
-    #[clap(short, long, default_value = "2,3-0,1")]
-    weights: ArgWeights,
-    #[clap(short = 'p', long, default_value = "700")]
-    starting_pts: usize,
-    #[clap(short, long, default_value = "100")]
-    scale: f64,
-    #[clap(short, long, default_value = "16")]
-    k_factor: f64,
-    #[clap(short = 't', long, default_value = "100")]
-    min_turns: usize,
-    #[clap(short = 'T', long, default_value = "200")]
-    max_turns: usize,
+    /// Settings in TOML, keyed as the fields of `EloPoolConfig`, overridden by the flags
+    #[clap(long)]
+    config: Option<PathBuf>,
+    /// Strategies in the pool, as `name` or `name:param,param...`, all the built-in ones if missing
+    #[clap(long)]
+    players: Option<PlayerList>,
+    /// Strategies to remove from the pool
+    #[clap(long)]
+    exclude: Vec<PlayerList>,
+    #[clap(short, long)]
+    weights: Option<Weights>,
+    #[clap(short = 'p', long)]
+    starting_pts: Option<f64>,
+    #[clap(short, long)]
+    scale: Option<f64>,
+    #[clap(short, long)]
+    k_factor: Option<f64>,
+    #[clap(short = 't', long)]
+    min_turns: Option<usize>,
+    #[clap(short = 'T', long)]
+    max_turns: Option<usize>,
+    /// Probability of each move being flipped
+    #[clap(short, long)]
+    noise: Option<f64>,
+    /// Probability of each move being seen flipped by the opponent
+    #[clap(long)]
+    perception_noise: Option<f64>,
+    /// Weight of each turn of a match relative to the previous one
+    #[clap(long)]
+    discount: Option<f64>,
+    /// Tell the players the length of each match
+    #[clap(long)]
+    known_horizon: bool,
+    /// Curve giving the expected outcome from the rating difference
+    #[clap(long, value_enum)]
+    expected: Option<ArgExpected>,
+    /// Decrease of the correction factor: `constant`, `harmonic:<hl>` or `exponential:<hl>`
+    #[clap(long)]
+    k_schedule: Option<ArgKSchedule>,
+    /// Maximum absolute score of a game considered a draw
+    #[clap(long)]
+    draw_epsilon: Option<f64>,
+    /// Choice of the players of a match: `uniform`, `proximity:<width>` or `fair`
+    #[clap(long)]
+    matchmaking: Option<ArgMatchmaking>,
+    /// Play each match in both orderings
+    #[clap(long)]
+    symmetric: bool,
+    /// Let the players remember their opponents across matches
+    #[clap(long)]
+    memory: bool,
+    /// Games played between the same players for each rating update
+    #[clap(long)]
+    series_len: Option<usize>,
 
     /// Refresh time in seconds
     #[clap(short, long, default_value = "2")]
     refresh: u64,
     #[clap(long)]
     seed: Option<u64>,
+    /// File where the pool is saved at each refresh, and resumed from with its settings if it exists
+    #[clap(long)]
+    checkpoint: Option<PathBuf>,
+    /// File where a row of ratings is appended at each refresh
+    #[clap(long)]
+    csv: Option<PathBuf>,
+    /// Stop after this many matches
+    #[clap(long)]
+    matches: Option<usize>,
+    /// Stop when no match of the last window corrects a rating by more than this
+    #[clap(long)]
+    until_converged: Option<f64>,
+    /// Number of matches over which the convergence is checked
+    #[clap(long, default_value = "10000")]
+    window: usize,
+    /// Output of the standings at each refresh
+    #[clap(long, value_enum, default_value = "table")]
+    format: Format,
+    /// Order of the standings
+    #[clap(long, value_enum, default_value = "rating")]
+    sort: SortKey,
+    /// Columns of the table
+    #[clap(
+        long,
+        value_enum,
+        value_delimiter = ',',
+        default_value = "name,rating,record,coop_rate,mutual_coop_rate,description"
+    )]
+    show: Vec<Column>,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Format {
+    /// A live table on the cleared screen
+    Table,
+    /// A JSON array per line
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum SortKey {
+    /// Highest rating first
+    Rating,
+    /// Alphabetical
+    Name,
+    /// Most games first
+    Games,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Column {
+    Name,
+    /// Rating, with its standard error
+    Rating,
+    /// Wins, losses and draws
+    Record,
+    Games,
+    /// Fraction of the turns the player collaborated
+    #[value(name = "coop_rate")]
+    CoopRate,
+    /// Fraction of the turns both players collaborated
+    #[value(name = "mutual_coop_rate")]
+    MutualCoopRate,
+    Description,
+}
+
+/// Comma separated players, whose parameters can also be separated by commas
+///
+/// A comma starts a new player only if it is followed by the name of a strategy.
+#[derive(Debug, Clone)]
+struct PlayerList(Vec<PlayerFactory>);
+impl FromStr for PlayerList {
+    type Err = ParsePlayerError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut players: Vec<String> = vec![];
+        for chunk in s.split(',') {
+            let name = chunk.split(':').next().unwrap_or_default();
+            let is_param = matches!(
+                name.parse::<PlayerFactory>(),
+                Err(ParsePlayerError::UnknownStrategy(_))
+            );
+            match players.last_mut() {
+                Some(player) if is_param => {
+                    player.push(',');
+                    player.push_str(chunk)
+                }
+                _ => players.push(chunk.to_owned()),
+            }
+        }
+        players
+            .iter()
+            .map(|player| player.parse())
+            .collect::<Result<_, _>>()
+            .map(PlayerList)
+    }
 }
 
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum ArgExpected {
+    Tanh,
+    Logistic,
+}
+impl From<ArgExpected> for ExpectedModel {
+    fn from(value: ArgExpected) -> Self {
+        match value {
+            ArgExpected::Tanh => ExpectedModel::Tanh,
+            ArgExpected::Logistic => ExpectedModel::Logistic,
+        }
+    }
+}
 #[derive(Debug, Clone)]
-struct ArgWeights(Weights);
-impl FromStr for ArgWeights {
+struct ArgKSchedule(KSchedule);
+impl FromStr for ArgKSchedule {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (_, defect_defect, defect_collab_0, defect_collab_1, collab_collab) =
-            regex_captures!(r"^(\d+),(\d+)\-(\d+),(\d+)", s)
-                .context("The weights must be in the format `dd,dcw-dcl,cc`")?;
-        Ok(ArgWeights(Weights {
-            defect_defect: defect_defect.parse().context("Integer overflow in dd")?,
-            defect_collab: (
-                defect_collab_0.parse().context("Integer overflow in dcw")?,
-                defect_collab_1.parse().context("Integer overflow in dcl")?,
-            ),
-            collab_collab: collab_collab.parse().context("Integer overflow in cc")?,
+        if s == "constant" {
+            return Ok(ArgKSchedule(KSchedule::Constant));
+        }
+        let (_, kind, halflife) = regex_captures!(r"^(harmonic|exponential):(.+)$", s).context(
+            "The schedule must be `constant`, `harmonic:<halflife>` or `exponential:<halflife>`",
+        )?;
+        let halflife: f64 = halflife.parse().context("Invalid halflife")?;
+        if halflife.is_nan() || halflife <= 0. {
+            bail!("The halflife must be positive, not {halflife}")
+        }
+        Ok(ArgKSchedule(match kind {
+            "harmonic" => KSchedule::Harmonic { halflife },
+            "exponential" => KSchedule::Exponential { halflife },
+            _ => unreachable!(),
+        }))
+    }
+}
+#[derive(Debug, Clone)]
+struct ArgMatchmaking(Matchmaking);
+impl FromStr for ArgMatchmaking {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "uniform" {
+            return Ok(ArgMatchmaking(Matchmaking::Uniform));
+        }
+        if s == "fair" {
+            return Ok(ArgMatchmaking(Matchmaking::Fair));
+        }
+        let (_, width) = regex_captures!(r"^proximity:(.+)$", s)
+            .context("The matchmaking must be `uniform`, `proximity:<width>` or `fair`")?;
+        Ok(ArgMatchmaking(Matchmaking::Proximity {
+            width: width.parse().context("Invalid width")?,
         }))
     }
 }
-impl From<&Args> for EloPoolConfig {
-    fn from(
-        Args {
-            weights,
-            starting_pts,
-            scale,
-            k_factor,
-            min_turns,
-            max_turns,
-            ..
-        }: &Args,
-    ) -> Self {
-        Self {
-            weights: weights.0,
-            starting_pts: *starting_pts,
-            scale: *scale,
-            k_factor: *k_factor,
-            min_turns: *min_turns,
-            max_turns: *max_turns,
+impl Args {
+    /// Override the settings given as flags
+    fn apply(&self, config: &mut EloPoolConfig) {
+        if let Some(PlayerList(players)) = &self.players {
+            config.roster = Some(players.clone())
+        }
+        if let Some(weights) = self.weights {
+            config.weights = weights
+        }
+        if let Some(starting_pts) = self.starting_pts {
+            config.starting_pts = starting_pts
+        }
+        if let Some(scale) = self.scale {
+            config.scale = scale
+        }
+        if let Some(k_factor) = self.k_factor {
+            config.k_factor = k_factor
+        }
+        if let Some(min_turns) = self.min_turns {
+            config.min_turns = min_turns
+        }
+        if let Some(max_turns) = self.max_turns {
+            config.max_turns = max_turns
+        }
+        if let Some(noise) = self.noise {
+            config.execution_noise = noise
+        }
+        if let Some(perception_noise) = self.perception_noise {
+            config.perception_noise = perception_noise
+        }
+        if let Some(discount) = self.discount {
+            config.discount = discount
+        }
+        if self.known_horizon {
+            config.known_horizon = true
+        }
+        if let Some(expected) = self.expected {
+            config.expected_model = expected.into()
+        }
+        if let Some(k_schedule) = &self.k_schedule {
+            config.k_schedule = k_schedule.0
+        }
+        if let Some(draw_epsilon) = self.draw_epsilon {
+            config.draw_epsilon = draw_epsilon
         }
+        if let Some(matchmaking) = &self.matchmaking {
+            config.matchmaking = matchmaking.0
+        }
+        if self.symmetric {
+            config.symmetric = true
+        }
+        if self.memory {
+            config.memory = true
+        }
+        if let Some(series_len) = self.series_len {
+            config.series_len = series_len
+        }
+    }
+
+    fn excluded(&self) -> impl Iterator<Item = &PlayerFactory> {
+        self.exclude.iter().flat_map(|PlayerList(players)| players)
+    }
+
+    /// Flags of the settings given here or in the config file, where `config` differs from `saved`
+    fn conflicts(
+        &self,
+        file_keys: &[String],
+        config: &EloPoolConfig,
+        saved: &EloPoolConfig,
+    ) -> Vec<&'static str> {
+        let kept = |roster: &[PlayerFactory]| -> Vec<PlayerFactory> {
+            roster
+                .iter()
+                .filter(|player| !self.excluded().any(|excluded| excluded == *player))
+                .cloned()
+                .collect()
+        };
+        [
+            (
+                "--players",
+                self.players.is_some(),
+                "roster",
+                config.roster.as_deref().map(kept) == saved.roster.as_deref().map(kept),
+            ),
+            (
+                "--weights",
+                self.weights.is_some(),
+                "weights",
+                config.weights == saved.weights,
+            ),
+            (
+                "--scale",
+                self.scale.is_some(),
+                "scale",
+                config.scale == saved.scale,
+            ),
+            (
+                "--k-factor",
+                self.k_factor.is_some(),
+                "k_factor",
+                config.k_factor == saved.k_factor,
+            ),
+            (
+                "--noise",
+                self.noise.is_some(),
+                "execution_noise",
+                config.execution_noise == saved.execution_noise,
+            ),
+            (
+                "--perception-noise",
+                self.perception_noise.is_some(),
+                "perception_noise",
+                config.perception_noise == saved.perception_noise,
+            ),
+            (
+                "--discount",
+                self.discount.is_some(),
+                "discount",
+                config.discount == saved.discount,
+            ),
+            (
+                "--known-horizon",
+                self.known_horizon,
+                "known_horizon",
+                config.known_horizon == saved.known_horizon,
+            ),
+            (
+                "--expected",
+                self.expected.is_some(),
+                "expected_model",
+                config.expected_model == saved.expected_model,
+            ),
+            (
+                "--k-schedule",
+                self.k_schedule.is_some(),
+                "k_schedule",
+                config.k_schedule == saved.k_schedule,
+            ),
+            (
+                "--draw-epsilon",
+                self.draw_epsilon.is_some(),
+                "draw_epsilon",
+                config.draw_epsilon == saved.draw_epsilon,
+            ),
+            (
+                "--matchmaking",
+                self.matchmaking.is_some(),
+                "matchmaking",
+                config.matchmaking == saved.matchmaking,
+            ),
+            (
+                "--symmetric",
+                self.symmetric,
+                "symmetric",
+                config.symmetric == saved.symmetric,
+            ),
+            (
+                "--memory",
+                self.memory,
+                "memory",
+                config.memory == saved.memory,
+            ),
+            (
+                "--series-len",
+                self.series_len.is_some(),
+                "series_len",
+                config.series_len == saved.series_len,
+            ),
+        ]
+        .into_iter()
+        .filter(|&(_, flag, key, same)| (flag || file_keys.iter().any(|k| k == key)) && !same)
+        .map(|(flag, ..)| flag)
+        .collect()
     }
 }
 
-fn main() -> anyhow::Result<!> {
+fn main() -> anyhow::Result<()> {
     let args = Args::parse();
 
-    let mut pool = EloPool::from(EloPoolConfig::from(&args));
-    let Args { refresh, seed, .. } = args;
+    let (mut config, file_keys) = match &args.config {
+        Some(path) => {
+            let file = fs::read_to_string(path)
+                .with_context(|| format!("Cannot read {}", path.display()))?;
+            let invalid = || format!("Invalid config {}", path.display());
+            let keys = toml::from_str::<toml::Table>(&file)
+                .with_context(invalid)?
+                .keys()
+                .cloned()
+                .collect();
+            (toml::from_str(&file).with_context(invalid)?, keys)
+        }
+        None => (EloPoolConfig::default(), vec![]),
+    };
+    args.apply(&mut config);
+    config.validate()?;
+    let (mut pool, resumed) = match &args.checkpoint {
+        Some(path) if path.exists() => {
+            let pool = EloPool::load(path, Uniform::new(config.min_turns, config.max_turns + 1))
+                .with_context(|| format!("Cannot resume from {}", path.display()))?;
+            // the turns are not saved in the checkpoint, and the starting points are not used
+            let conflicts = args.conflicts(&file_keys, &config, &pool.snapshot().config());
+            if !conflicts.is_empty() {
+                bail!(
+                    "{} holds different settings for {}: only the turns can be changed when \
+                    resuming",
+                    path.display(),
+                    conflicts.join(", ")
+                )
+            }
+            (pool, true)
+        }
+        _ => (EloPool::from(config), false),
+    };
+    let Args {
+        exclude,
+        refresh,
+        seed,
+        checkpoint,
+        csv,
+        matches,
+        until_converged,
+        window,
+        format,
+        sort,
+        show,
+        ..
+    } = args;
+    for excluded in exclude.into_iter().flat_map(|PlayerList(players)| players) {
+        let index = pool.ratings().position(|(player, _)| *player == excluded);
+        match index {
+            Some(index) => {
+                pool.remove_player(index);
+            }
+            // already removed before the checkpoint
+            None if resumed => {}
+            None => bail!("{} is not in the pool", excluded.name().trim_end()),
+        }
+    }
+    if until_converged.is_some() {
+        pool = pool.with_delta_window(window)
+    }
+    if pool.ratings().count() < 2 {
+        bail!("At least two strategies are needed to play")
+    }
     let refresh = Duration::from_secs(refresh);
+    let mut csv = csv
+        .map(|path| {
+            CsvLog::open(&path, &pool).with_context(|| format!("Cannot open {}", path.display()))
+        })
+        .transpose()?;
 
     let mut rng = if let Some(seed) = seed {
         SmallRng::seed_from_u64(seed)
@@ -90,21 +494,216 @@ fn main() -> anyhow::Result<!> {
         SmallRng::from_entropy()
     };
 
+    let stop = Arc::new(AtomicBool::new(false));
+    ctrlc::set_handler({
+        let stop = stop.clone();
+        move || stop.store(true, Ordering::Relaxed)
+    })
+    .context("Cannot install the Ctrl-C handler")?;
+    if matches == Some(0) {
+        stop.store(true, Ordering::Relaxed)
+    }
+    let mut played = 0;
+    let mut throughput = 0.;
+    let mut out = io::stdout().lock();
+
     loop {
-        print_pool(&pool)?;
-        let start = Instant::now();
-        while Instant::now() < start + refresh {
+        let printed = print_pool(&mut out, &pool, format, sort, &show).and_then(|()| {
+            if let Format::Table = format {
+                writeln!(
+                    out,
+                    "\nmatches/s: {}   total: {}",
+                    human(throughput),
+                    human(played as f64)
+                )?;
+            }
+            Ok(out.flush()?)
+        });
+        match printed {
+            // nobody reads the standings anymore, e.g. `fight --format json | head`
+            Err(err) if is_broken_pipe(&err) => stop.store(true, Ordering::Relaxed),
+            printed => printed?,
+        }
+        if let Some(path) = &checkpoint {
+            pool.save(path)
+                .with_context(|| format!("Cannot save to {}", path.display()))?;
+        }
+        if let Some(csv) = &mut csv {
+            csv.append(&pool).context("Cannot write the csv")?;
+        }
+        if stop.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+        let (start, played_before) = (Instant::now(), played);
+        while Instant::now() < start + refresh && !stop.load(Ordering::Relaxed) {
             pool.play(&mut rng);
+            played += 1;
+            if matches.is_some_and(|matches| played >= matches) {
+                stop.store(true, Ordering::Relaxed)
+            }
+            if until_converged
+                .is_some_and(|threshold| pool.max_recent_delta().is_some_and(|d| d <= threshold))
+            {
+                stop.store(true, Ordering::Relaxed)
+            }
+        }
+        throughput = (played - played_before) as f64 / start.elapsed().as_secs_f64();
+    }
+}
+
+/// Format a count with a metric suffix, e.g. `48.3M`
+fn human(count: f64) -> String {
+    match count {
+        c if c >= 1e9 => format!("{:.1}G", c / 1e9),
+        c if c >= 1e6 => format!("{:.1}M", c / 1e6),
+        c if c >= 1e3 => format!("{:.1}k", c / 1e3),
+        c => format!("{:.0}", c),
+    }
+}
+
+/// Quote a field of a csv, if needed
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+/// Ratings log, with a column per strategy sorted by name
+struct CsvLog {
+    file: File,
+    columns: Vec<usize>,
+}
+impl CsvLog {
+    /// Open the log, appending to it if it has the same columns
+    fn open(path: &Path, pool: &EloPool<Uniform<usize>>) -> anyhow::Result<Self> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(path)?;
+        let names: Vec<_> = pool
+            .ratings()
+            .map(|(player, _)| player.name().trim_end().to_owned())
+            .collect();
+        let mut columns: Vec<_> = (0..names.len()).collect();
+        columns.sort_by_key(|&i| &names[i]);
+        let header: Vec<_> = ["timestamp"]
+            .into_iter()
+            .chain(columns.iter().map(|&i| names[i].as_str()))
+            .map(csv_field)
+            .collect();
+        let header = header.join(",");
+        if file.metadata()?.len() == 0 {
+            writeln!(file, "{header}")?;
+        } else {
+            let mut existing = String::new();
+            BufReader::new(&file).read_line(&mut existing)?;
+            if existing.trim_end_matches(['\r', '\n']) != header {
+                bail!("The existing columns do not match the players in the pool")
+            }
         }
+        Ok(Self { file, columns })
     }
+
+    fn append(&mut self, pool: &EloPool<Uniform<usize>>) -> io::Result<()> {
+        let ratings: Vec<_> = pool.ratings().map(|(_, rating)| rating.rating).collect();
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let row: Vec<_> = self
+            .columns
+            .iter()
+            .map(|&i| format!("{:.1}", ratings[i]))
+            .collect();
+        writeln!(self.file, "{},{}", timestamp, row.join(","))
+    }
+}
+
+#[derive(Serialize)]
+struct Standing {
+    name: String,
+    rating: f64,
+    /// Missing until the error can be estimated
+    std_err: Option<f64>,
+    description: String,
+    games: usize,
+    cooperation_rate: f64,
+    mutual_cooperation_rate: f64,
 }
 
-fn print_pool(pool: &EloPool<rand::distributions::Uniform<usize>>) -> anyhow::Result<()> {
-    let mut ratings = pool.ratings().to_owned();
-    ratings.sort_by_key(|(_, r)| Reverse(*r));
-    clearscreen::clear()?;
-    for (player, rating) in ratings {
-        println!("{}\t{}\t({})", player.name(), rating, player.description())
+/// Whether the error comes from the output being closed
+fn is_broken_pipe(err: &anyhow::Error) -> bool {
+    err.downcast_ref::<io::Error>()
+        .is_some_and(|err| err.kind() == io::ErrorKind::BrokenPipe)
+}
+
+fn print_pool(
+    out: &mut impl Write,
+    pool: &EloPool<Uniform<usize>>,
+    format: Format,
+    sort: SortKey,
+    columns: &[Column],
+) -> anyhow::Result<()> {
+    let mut ratings: Vec<_> = pool
+        .ratings()
+        .zip(pool.stats())
+        .map(|((player, rating), (_, stats))| (player, rating, stats))
+        .collect();
+    match sort {
+        SortKey::Rating => {
+            ratings.sort_by(|(_, r1, _), (_, r2, _)| r2.rating.total_cmp(&r1.rating))
+        }
+        SortKey::Name => {
+            ratings.sort_by(|(p1, _, _), (p2, _, _)| p1.name().trim_end().cmp(p2.name().trim_end()))
+        }
+        SortKey::Games => ratings.sort_by_key(|(_, rating, _)| Reverse(rating.games)),
+    }
+    match format {
+        Format::Table => {
+            clearscreen::clear()?;
+            for (player, rating, stats) in ratings {
+                let cells: Vec<_> = columns
+                    .iter()
+                    .map(|column| match column {
+                        Column::Name => player.name().into_owned(),
+                        Column::Rating if rating.std_err.is_finite() => {
+                            format!("{:.0} ± {:.1}", rating.rating, rating.std_err)
+                        }
+                        Column::Rating => format!("{:.0} ± ?", rating.rating),
+                        Column::Record => {
+                            format!("{}/{}/{}", stats.wins, stats.losses, stats.draws)
+                        }
+                        Column::Games => stats.games.to_string(),
+                        Column::CoopRate => {
+                            format!("{:.0}% C", 100. * stats.behavior.cooperation_rate())
+                        }
+                        Column::MutualCoopRate => {
+                            format!("{:.0}% CC", 100. * stats.behavior.mutual_cooperation_rate())
+                        }
+                        Column::Description => format!("({})", player.description()),
+                    })
+                    .collect();
+                writeln!(out, "{}", cells.join("\t"))?
+            }
+        }
+        Format::Json => {
+            let standings: Vec<_> = ratings
+                .into_iter()
+                .map(|(player, rating, stats)| Standing {
+                    name: player.name().trim_end().to_owned(),
+                    rating: rating.rating,
+                    std_err: rating.std_err.is_finite().then_some(rating.std_err),
+                    description: player.description().into_owned(),
+                    games: stats.games,
+                    cooperation_rate: stats.behavior.cooperation_rate(),
+                    mutual_cooperation_rate: stats.behavior.mutual_cooperation_rate(),
+                })
+                .collect();
+            writeln!(out, "{}", serde_json::to_string(&standings)?)?
+        }
     }
     Ok(())
 }
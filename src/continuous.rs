@@ -0,0 +1,227 @@
+use std::borrow::Cow;
+
+use rand::{distributions::Distribution, Rng, RngCore};
+
+use crate::{
+    play_generic, uniform_pair, EloUpdate, ExpectedModel, KSchedule, Payoff, PlayerStats, Strategy,
+    Turn, Weights,
+};
+
+/// Payoffs of the continuous game, where each move is a level of collaboration in `[0, 1]`
+///
+/// The outcome of a turn interpolates bilinearly between the outcomes of the discrete game.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct ContinuousWeights {
+    pub defect_defect: f64,
+    pub defect_collab: (f64, f64),
+    pub collab_collab: f64,
+}
+impl ContinuousWeights {
+    /// The donation game: collaborating at level `x` costs `cost * x` and gives `benefit * x`
+    pub fn donation(benefit: f64, cost: f64) -> Self {
+        Self {
+            defect_defect: 0.,
+            defect_collab: (benefit, -cost),
+            collab_collab: benefit - cost,
+        }
+    }
+
+    #[must_use]
+    #[inline]
+    fn outcome(&self, x1: f64, x2: f64) -> (f64, f64) {
+        let (d1, d2) = (1. - x1, 1. - x2);
+        (
+            x1 * x2 * self.collab_collab
+                + x1 * d2 * self.defect_collab.1
+                + d1 * x2 * self.defect_collab.0
+                + d1 * d2 * self.defect_defect,
+            x1 * x2 * self.collab_collab
+                + x1 * d2 * self.defect_collab.0
+                + d1 * x2 * self.defect_collab.1
+                + d1 * d2 * self.defect_defect,
+        )
+    }
+    #[must_use]
+    #[inline]
+    fn max_diff(&self) -> f64 {
+        (self.defect_collab.0 - self.defect_collab.1).abs()
+    }
+}
+impl From<Weights> for ContinuousWeights {
+    fn from(value: Weights) -> Self {
+        Self {
+            defect_defect: value.defect_defect as f64,
+            defect_collab: (value.defect_collab.0 as f64, value.defect_collab.1 as f64),
+            collab_collab: value.collab_collab as f64,
+        }
+    }
+}
+impl Payoff<f64> for ContinuousWeights {
+    fn payoff(&self, x1: f64, x2: f64) -> (f64, f64) {
+        self.outcome(x1, x2)
+    }
+}
+
+/// A player of the continuous game
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ContinuousPlayer {
+    /// Always collaborate at the same level
+    Constant(f64),
+    TitForTat,
+    /// Answer with the last level of the opponent, raised by a fixed amount
+    Generous(f64),
+    /// Start low, and raise the level by a step each time the opponent matched it
+    RaiseTheStakes(f64),
+}
+impl ContinuousPlayer {
+    pub fn name(&self) -> Cow<'static, str> {
+        match self {
+            ContinuousPlayer::Constant(x) => format!("Constant {:.0}%", 100. * x).into(),
+            ContinuousPlayer::TitForTat => "TitForTat".into(),
+            ContinuousPlayer::Generous(g) => format!("Generous {:.0}%", 100. * g).into(),
+            ContinuousPlayer::RaiseTheStakes(step) => {
+                format!("RaiseTheStakes {:.0}%", 100. * step).into()
+            }
+        }
+    }
+    pub fn description(&self) -> Cow<'static, str> {
+        match self {
+            ContinuousPlayer::Constant(x) => {
+                format!("Always collaborate at {:.0}%", 100. * x).into()
+            }
+            ContinuousPlayer::TitForTat => {
+                "Collaborate fully, then answer with the last level".into()
+            }
+            ContinuousPlayer::Generous(g) => format!(
+                "Collaborate fully, then answer with the last level raised by {:.0}%",
+                100. * g
+            )
+            .into(),
+            ContinuousPlayer::RaiseTheStakes(step) => format!(
+                "Start at {0:.0}%, raise by {0:.0}% while matched, else answer with the last level",
+                100. * step
+            )
+            .into(),
+        }
+    }
+}
+impl Strategy<f64> for ContinuousPlayer {
+    fn play(&mut self, turn: Turn<'_, f64>, _rng: &mut dyn RngCore) -> f64 {
+        let level = match (*self, turn.me.last(), turn.them.last()) {
+            (ContinuousPlayer::Constant(x), _, _) => x,
+            (ContinuousPlayer::TitForTat, _, Some(their)) => *their,
+            (ContinuousPlayer::Generous(g), _, Some(their)) => their + g,
+            (ContinuousPlayer::TitForTat | ContinuousPlayer::Generous(_), _, None) => 1.,
+            (ContinuousPlayer::RaiseTheStakes(step), Some(mine), Some(their)) => {
+                if their >= mine {
+                    mine + step
+                } else {
+                    *their
+                }
+            }
+            (ContinuousPlayer::RaiseTheStakes(step), _, _) => step,
+        };
+        level.clamp(0., 1.)
+    }
+}
+
+/// Play a match of the continuous game, returning the points and the normalized score
+pub fn play_match(
+    p1: &ContinuousPlayer,
+    p2: &ContinuousPlayer,
+    weights: &ContinuousWeights,
+    turns: usize,
+    rng: &mut impl Rng,
+) -> ((f64, f64), f64) {
+    let (points, _) = play_generic(&mut p1.clone(), &mut p2.clone(), weights, turns, rng);
+    let max_diff = turns as f64 * weights.max_diff();
+    let score = if max_diff > 0. {
+        (points.0 - points.1) / max_diff
+    } else {
+        0.
+    };
+    (points, score)
+}
+
+/// A pool of continuous players, rated like an `EloPool`
+pub struct ContinuousPool<TD>
+where
+    TD: Distribution<usize>,
+{
+    players: Vec<(ContinuousPlayer, f64, PlayerStats)>,
+    weights: ContinuousWeights,
+    turn_distr: TD,
+
+    // settings of the Elo correction, as in `EloPool`
+    scale: f64,
+    k_factor: f64,
+    expected_model: ExpectedModel,
+    draw_epsilon: f64,
+}
+impl<TD> ContinuousPool<TD>
+where
+    TD: Distribution<usize>,
+{
+    pub fn new(
+        weights: ContinuousWeights,
+        turn_distr: TD,
+        roster: impl IntoIterator<Item = ContinuousPlayer>,
+        starting_pts: f64,
+        scale: f64,
+        k_factor: f64,
+    ) -> Self {
+        Self {
+            players: roster
+                .into_iter()
+                .map(|p| (p, starting_pts, PlayerStats::default()))
+                .collect(),
+            weights,
+            turn_distr,
+            scale,
+            k_factor,
+            expected_model: ExpectedModel::default(),
+            draw_epsilon: 0.05,
+        }
+    }
+
+    pub fn with_expected_model(mut self, expected_model: ExpectedModel) -> Self {
+        self.expected_model = expected_model;
+        self
+    }
+
+    pub fn with_draw_epsilon(mut self, draw_epsilon: f64) -> Self {
+        self.draw_epsilon = draw_epsilon;
+        self
+    }
+
+    pub fn play(&mut self, rng: &mut impl Rng) {
+        if self.players.len() < 2 {
+            return;
+        }
+        let [i1, i2] = uniform_pair(self.players.len(), rng);
+        let turns = self.turn_distr.sample(rng);
+        let (_, outcome) = play_match(
+            &self.players[i1].0,
+            &self.players[i2].0,
+            &self.weights,
+            turns,
+            rng,
+        );
+        EloUpdate {
+            scale: self.scale,
+            k_factor: self.k_factor,
+            k_schedule: KSchedule::Constant,
+            expected_model: self.expected_model,
+            draw_epsilon: self.draw_epsilon,
+        }
+        .apply(&mut self.players, [i1, i2], outcome);
+    }
+
+    pub fn ratings(&self) -> impl Iterator<Item = (&ContinuousPlayer, f64)> + '_ {
+        self.players.iter().map(|(p, r, _)| (p, *r))
+    }
+
+    pub fn stats(&self) -> impl Iterator<Item = (&ContinuousPlayer, &PlayerStats)> + '_ {
+        self.players.iter().map(|(p, _, s)| (p, s))
+    }
+}
@@ -0,0 +1,184 @@
+use rand::{
+    distributions::{Distribution, WeightedIndex},
+    Rng,
+};
+
+use crate::{play_match, PlayerFactory, Weights};
+
+/// A population evolving with the replicator dynamics
+///
+/// Each strategy grows in proportion to its average payoff against the current
+/// population.
+#[derive(Debug, Clone)]
+pub struct ReplicatorPool {
+    factories: Vec<PlayerFactory>,
+    frequencies: Vec<f64>,
+    weights: Weights,
+}
+impl ReplicatorPool {
+    /// Create a population. The frequencies are normalized to sum to one
+    ///
+    /// # Panics
+    ///
+    /// If the frequencies are not one per strategy, or their total is not positive.
+    pub fn new(factories: Vec<PlayerFactory>, frequencies: Vec<f64>, weights: Weights) -> Self {
+        assert_eq!(
+            factories.len(),
+            frequencies.len(),
+            "Each strategy needs a frequency"
+        );
+        let total: f64 = frequencies.iter().sum();
+        assert!(total > 0., "The frequencies must have a positive total");
+        Self {
+            factories,
+            frequencies: frequencies.into_iter().map(|f| f / total).collect(),
+            weights,
+        }
+    }
+
+    /// Advance the population by a generation
+    pub fn step(&mut self, turns: usize, rng: &mut impl Rng) {
+        let fitness: Vec<f64> = self
+            .factories
+            .iter()
+            .zip(&self.frequencies)
+            .map(|(p1, x1)| {
+                if *x1 == 0. {
+                    return 0.;
+                }
+                self.factories
+                    .iter()
+                    .zip(&self.frequencies)
+                    .filter(|(_, x2)| **x2 > 0.)
+                    .map(|(p2, x2)| {
+                        let points = play_match(p1, p2, &self.weights, turns, rng).points.0;
+                        x2 * points as f64 / turns.max(1) as f64
+                    })
+                    .sum()
+            })
+            .collect();
+        let mean: f64 = fitness
+            .iter()
+            .zip(&self.frequencies)
+            .map(|(f, x)| f * x)
+            .sum();
+        if mean <= 0. {
+            // nobody gains anything, nothing changes
+            return;
+        }
+        for (x, f) in self.frequencies.iter_mut().zip(fitness) {
+            *x *= f / mean;
+        }
+    }
+
+    pub fn factories(&self) -> &[PlayerFactory] {
+        &self.factories
+    }
+
+    /// Current share of the population of each strategy
+    pub fn frequencies(&self) -> &[f64] {
+        &self.frequencies
+    }
+}
+
+/// A finite population evolving with a Moran process
+///
+/// At each step an individual reproduces, chosen with probability proportional
+/// to its fitness, and its copy replaces an individual chosen uniformly.
+#[derive(Debug, Clone)]
+pub struct MoranPool {
+    composition: Vec<(PlayerFactory, usize)>,
+    weights: Weights,
+}
+impl MoranPool {
+    pub fn new(composition: Vec<(PlayerFactory, usize)>, weights: Weights) -> Self {
+        Self {
+            composition,
+            weights,
+        }
+    }
+
+    /// Make an individual reproduce and another die
+    pub fn step(&mut self, turns: usize, rng: &mut impl Rng) {
+        let size: usize = self.composition.iter().map(|(_, n)| n).sum();
+        if size < 2 {
+            return;
+        }
+        // average points per turn of each strategy against the rest of the population
+        let fitness: Vec<f64> = self
+            .composition
+            .iter()
+            .enumerate()
+            .map(|(i1, (p1, n1))| {
+                if *n1 == 0 {
+                    return 0.;
+                }
+                self.composition
+                    .iter()
+                    .enumerate()
+                    .map(|(i2, (p2, n2))| {
+                        let opponents = if i1 == i2 { n2 - 1 } else { *n2 };
+                        if opponents == 0 {
+                            return 0.;
+                        }
+                        let points = play_match(p1, p2, &self.weights, turns, rng).points.0;
+                        opponents as f64 * points as f64 / turns.max(1) as f64
+                    })
+                    .sum::<f64>()
+                    / (size - 1) as f64
+            })
+            .collect();
+        let counts = self.composition.iter().map(|(_, n)| *n as f64);
+        let fit = counts.clone().zip(&fitness).map(|(n, f)| n * f);
+        let reproducing = match WeightedIndex::new(fit) {
+            Ok(distr) => distr.sample(rng),
+            // nobody is fit, everyone reproduces alike
+            Err(_) => WeightedIndex::new(counts.clone()).unwrap().sample(rng),
+        };
+        let dying = WeightedIndex::new(counts).unwrap().sample(rng);
+        self.composition[dying].1 -= 1;
+        self.composition[reproducing].1 += 1;
+    }
+
+    /// Number of individuals of each strategy
+    pub fn composition(&self) -> &[(PlayerFactory, usize)] {
+        &self.composition
+    }
+
+    /// The only strategy left in the population, if any
+    pub fn fixated(&self) -> Option<&PlayerFactory> {
+        let mut alive = self.composition.iter().filter(|(_, n)| *n > 0);
+        match (alive.next(), alive.next()) {
+            (Some((p, _)), None) => Some(p),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::SmallRng, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn replicator_without_turns_stays_still() {
+        let mut pool = ReplicatorPool::new(
+            vec![PlayerFactory::Defector, PlayerFactory::Collaborator],
+            vec![1., 3.],
+            Weights::default(),
+        );
+        pool.step(0, &mut SmallRng::seed_from_u64(0));
+        assert_eq!(pool.frequencies(), [0.25, 0.75]);
+    }
+
+    #[test]
+    #[should_panic = "positive total"]
+    fn replicator_rejects_empty_frequencies() {
+        ReplicatorPool::new(
+            vec![PlayerFactory::Defector, PlayerFactory::Collaborator],
+            vec![0., 0.],
+            Weights::default(),
+        );
+    }
+}
@@ -0,0 +1,102 @@
+use rand::{Rng, RngCore};
+
+use crate::{play_match, Choice, CustomFactory, PlayerFactory, Strategy, Turn, Weights};
+
+/// Tit for tat, or its dual, overridden by a collaboration with probability `collab` or a
+/// defection with probability `defect`
+#[derive(Debug, Clone, Copy)]
+struct JossAnn {
+    collab: f64,
+    defect: f64,
+    /// Play the dual of tit for tat: open with a defection, then answer with the opposite of the
+    /// last move
+    dual: bool,
+}
+impl Strategy for JossAnn {
+    fn play(&mut self, turn: Turn<'_>, rng: &mut dyn RngCore) -> Choice {
+        let r: f64 = rng.gen();
+        if r < self.collab {
+            Choice::Collab
+        } else if r < self.collab + self.defect {
+            Choice::Defect
+        } else {
+            match turn.them.last() {
+                Some(last) if self.dual => !*last,
+                Some(last) => *last,
+                None => (!self.dual).into(),
+            }
+        }
+    }
+}
+
+/// Fingerprint of a strategy: its points per turn against the Joss-Ann tit for tat probe
+///
+/// The probe collaborates with probability `x` and defects with probability `y`, playing tit for
+/// tat otherwise. Where `x + y > 1` the dual probe is used instead: it collaborates with
+/// probability `1 - y`, defects with probability `1 - x`, and otherwise plays the dual of tit for
+/// tat, answering with the opposite of the last move after opening with a defection.
+///
+/// The result is indexed as `[x][y]`, both going from 0 to 1 in `resolution` steps.
+pub fn fingerprint(
+    factory: &PlayerFactory,
+    weights: &Weights,
+    resolution: usize,
+    turns: usize,
+    rng: &mut impl Rng,
+) -> Vec<Vec<f64>> {
+    let step = resolution.saturating_sub(1).max(1) as f64;
+    (0..resolution)
+        .map(|i| {
+            (0..resolution)
+                .map(|j| {
+                    let (x, y) = (i as f64 / step, j as f64 / step);
+                    let probe = if x + y <= 1. {
+                        JossAnn {
+                            collab: x,
+                            defect: y,
+                            dual: false,
+                        }
+                    } else {
+                        JossAnn {
+                            collab: 1. - y,
+                            defect: 1. - x,
+                            dual: true,
+                        }
+                    };
+                    let probe = PlayerFactory::Custom(CustomFactory::new(
+                        "JossAnn",
+                        "Tit for tat, with random collaborations and defections",
+                        move |_, _| Box::new(probe),
+                    ));
+                    let points = play_match(factory, &probe, weights, turns, rng).points.0;
+                    points as f64 / turns.max(1) as f64
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::SmallRng, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn collaborator_fingerprint_corners() {
+        let weights = Weights {
+            defect_defect: 2,
+            defect_collab: (5, 1),
+            collab_collab: 3,
+        };
+        let print = fingerprint(
+            &PlayerFactory::Collaborator,
+            &weights,
+            2,
+            20,
+            &mut SmallRng::seed_from_u64(0),
+        );
+        let (collab, sucker) = (weights.collab_collab as f64, weights.defect_collab.1 as f64);
+        assert_eq!(print, vec![vec![collab, sucker], vec![collab, sucker]]);
+    }
+}
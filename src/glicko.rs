@@ -0,0 +1,180 @@
+use std::f64::consts::PI;
+
+use rand::{distributions::Distribution, Rng};
+
+use crate::{uniform_pair, Encounters, MatchBuffers, PlayerFactory, PlayerStats, Rules, Weights};
+
+/// Ratio between the Glicko and the Glicko-2 scales
+const GLICKO2_SCALE: f64 = 173.7178;
+/// Tolerance of the iteration finding the new volatility
+const CONVERGENCE: f64 = 1e-6;
+
+/// Rating of a player, with its uncertainty, on the Glicko scale
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Glicko2Rating {
+    pub rating: f64,
+    /// Standard deviation of the rating
+    pub deviation: f64,
+    /// Expected fluctuation of the rating
+    pub volatility: f64,
+}
+impl Default for Glicko2Rating {
+    fn default() -> Self {
+        Self {
+            rating: 1500.,
+            deviation: 350.,
+            volatility: 0.06,
+        }
+    }
+}
+impl Glicko2Rating {
+    /// Update the rating after a game of `score` in `[0, 1]` against `opponent`
+    fn update(&mut self, opponent: &Glicko2Rating, score: f64, tau: f64) {
+        let mu = (self.rating - 1500.) / GLICKO2_SCALE;
+        let phi = self.deviation / GLICKO2_SCALE;
+        let mu_j = (opponent.rating - 1500.) / GLICKO2_SCALE;
+        let phi_j = opponent.deviation / GLICKO2_SCALE;
+
+        let g = 1. / (1. + 3. * phi_j.powi(2) / PI.powi(2)).sqrt();
+        let expected = 1. / (1. + (-g * (mu - mu_j)).exp());
+        let v = 1. / (g.powi(2) * expected * (1. - expected));
+        let delta = v * g * (score - expected);
+
+        // new volatility, by the Illinois algorithm
+        let a = self.volatility.powi(2).ln();
+        let f = |x: f64| {
+            let ex = x.exp();
+            ex * (delta.powi(2) - phi.powi(2) - v - ex) / (2. * (phi.powi(2) + v + ex).powi(2))
+                - (x - a) / tau.powi(2)
+        };
+        let mut big_a = a;
+        let mut big_b = if delta.powi(2) > phi.powi(2) + v {
+            (delta.powi(2) - phi.powi(2) - v).ln()
+        } else {
+            let mut k = 1.;
+            while f(a - k * tau) < 0. {
+                k += 1.;
+            }
+            a - k * tau
+        };
+        let (mut f_a, mut f_b) = (f(big_a), f(big_b));
+        while (big_b - big_a).abs() > CONVERGENCE {
+            let big_c = big_a + (big_a - big_b) * f_a / (f_b - f_a);
+            let f_c = f(big_c);
+            if f_c * f_b <= 0. {
+                (big_a, f_a) = (big_b, f_b);
+            } else {
+                f_a /= 2.;
+            }
+            (big_b, f_b) = (big_c, f_c);
+        }
+        let volatility = (big_a / 2.).exp();
+
+        let phi_star = (phi.powi(2) + volatility.powi(2)).sqrt();
+        let phi = 1. / (1. / phi_star.powi(2) + 1. / v).sqrt();
+        let mu = mu + phi.powi(2) * g * (score - expected);
+
+        *self = Self {
+            rating: GLICKO2_SCALE * mu + 1500.,
+            deviation: GLICKO2_SCALE * phi,
+            volatility,
+        }
+    }
+}
+
+/// A pool of players rated with Glicko-2, each match being a rating period
+pub struct Glicko2Pool<TD>
+where
+    TD: Distribution<usize>,
+{
+    /// Players, with their rating and record
+    players: Vec<(PlayerFactory, Glicko2Rating, PlayerStats)>,
+    weights: Weights,
+    rules: Rules,
+    turn_distr: TD,
+
+    /// Constraint on the change of the volatility
+    tau: f64,
+    /// Maximum absolute score of a game considered a draw
+    draw_epsilon: f64,
+    buffers: MatchBuffers,
+}
+impl<TD> Glicko2Pool<TD>
+where
+    TD: Distribution<usize>,
+{
+    pub fn new(
+        weights: Weights,
+        turn_distr: TD,
+        roster: impl IntoIterator<Item = PlayerFactory>,
+    ) -> Self {
+        Self {
+            players: roster
+                .into_iter()
+                .map(|p| (p, Glicko2Rating::default(), PlayerStats::default()))
+                .collect(),
+            weights,
+            rules: Rules::default(),
+            turn_distr,
+            tau: 0.5,
+            draw_epsilon: 0.05,
+            buffers: MatchBuffers::default(),
+        }
+    }
+
+    /// Play the matches with the given rules
+    pub fn with_rules(mut self, rules: Rules) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    pub fn with_tau(mut self, tau: f64) -> Self {
+        self.tau = tau;
+        self
+    }
+
+    pub fn with_draw_epsilon(mut self, draw_epsilon: f64) -> Self {
+        self.draw_epsilon = draw_epsilon;
+        self
+    }
+
+    pub fn add_player(&mut self, factory: PlayerFactory, rating: Glicko2Rating) {
+        self.players.push((factory, rating, PlayerStats::default()))
+    }
+
+    pub fn play(&mut self, rng: &mut impl Rng) {
+        if self.players.len() < 2 {
+            return;
+        }
+        let [i1, i2] = uniform_pair(self.players.len(), rng);
+        let (p1, p2) = (&self.players[i1].0, &self.players[i2].0);
+        let turns = self.turn_distr.sample(rng);
+        let new = Encounters::default();
+        let outcome = self
+            .rules
+            .run_match(
+                p1,
+                p2,
+                &self.weights,
+                turns,
+                (&new, &new),
+                &mut self.buffers,
+                rng,
+            )
+            .score;
+        let (r1, r2) = (self.players[i1].1, self.players[i2].1);
+        for (i, opponent, outcome) in [(i1, r2, outcome), (i2, r1, -outcome)] {
+            let (_, rating, stats) = &mut self.players[i];
+            rating.update(&opponent, (outcome + 1.) / 2., self.tau);
+            stats.record(outcome, self.draw_epsilon);
+        }
+    }
+
+    pub fn ratings(&self) -> impl Iterator<Item = (&PlayerFactory, Glicko2Rating)> + '_ {
+        self.players.iter().map(|(p, r, _)| (p, *r))
+    }
+
+    pub fn stats(&self) -> impl Iterator<Item = (&PlayerFactory, &PlayerStats)> + '_ {
+        self.players.iter().map(|(p, _, s)| (p, s))
+    }
+}
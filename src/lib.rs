@@ -1,15 +1,50 @@
 #![feature(const_trait_impl)]
 #![feature(map_many_mut)]
 
-use std::borrow::Cow;
+use std::{
+    borrow::Cow,
+    collections::{HashMap, VecDeque},
+    error::Error,
+    fmt::{Debug, Display},
+    fs::{self, File},
+    io::{self, BufReader, BufWriter},
+    ops::Not,
+    path::Path,
+    str::FromStr,
+    sync::Arc,
+};
 
-use rand::{distributions::Uniform, prelude::Distribution, Rng};
-use serde::Deserialize;
+use lazy_regex::regex_captures;
+use rand::{
+    distributions::{Uniform, WeightedIndex},
+    prelude::Distribution,
+    rngs::SmallRng,
+    Rng, RngCore, SeedableRng,
+};
+use serde::{Deserialize, Serialize};
+
+pub mod continuous;
+mod evolution;
+mod fingerprint;
+mod glicko;
+mod record;
+mod tournament;
+pub use evolution::{MoranPool, ReplicatorPool};
+pub use fingerprint::fingerprint;
+pub use glicko::{Glicko2Pool, Glicko2Rating};
+pub use record::{play_match_recorded, verify_record, MatchRecord};
+pub use tournament::{
+    best_response, noise_sweep, payoff_matrix, PayoffTable, Tournament, TournamentResult,
+};
 
 /// Possible choices
-#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+///
+/// Serialized as `"C"` and `"D"`.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize)]
 pub enum Choice {
+    #[serde(rename = "D", alias = "Defect")]
     Defect,
+    #[serde(rename = "C", alias = "Collab")]
     Collab,
 }
 impl From<bool> for Choice {
@@ -20,15 +55,155 @@ impl From<bool> for Choice {
         }
     }
 }
+impl Not for Choice {
+    type Output = Self;
+
+    fn not(self) -> Self::Output {
+        match self {
+            Choice::Defect => Choice::Collab,
+            Choice::Collab => Choice::Defect,
+        }
+    }
+}
+impl TryFrom<char> for Choice {
+    type Error = char;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        match value {
+            'C' | 'c' => Ok(Self::Collab),
+            'D' | 'd' => Ok(Self::Defect),
+            _ => Err(value),
+        }
+    }
+}
+impl From<Choice> for char {
+    fn from(value: Choice) -> Self {
+        match value {
+            Choice::Collab => 'C',
+            Choice::Defect => 'D',
+        }
+    }
+}
+impl Display for Choice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", char::from(*self))
+    }
+}
+
+/// The moves of a player, written as `CDDC`
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Default, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub struct History(pub Vec<Choice>);
+impl Display for History {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.iter().try_for_each(|choice| write!(f, "{choice}"))
+    }
+}
+impl FromStr for History {
+    type Err = ParseChoiceError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.chars()
+            .map(|c| Choice::try_from(c).map_err(ParseChoiceError))
+            .collect::<Result<_, _>>()
+            .map(History)
+    }
+}
+impl From<History> for String {
+    fn from(value: History) -> Self {
+        value.to_string()
+    }
+}
+impl TryFrom<String> for History {
+    type Error = ParseChoiceError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+impl From<Vec<Choice>> for History {
+    fn from(value: Vec<Choice>) -> Self {
+        Self(value)
+    }
+}
+
+/// A character that is not a move
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseChoiceError(pub char);
+impl Display for ParseChoiceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Invalid move `{}`, expected `C` or `D`", self.0)
+    }
+}
+impl Error for ParseChoiceError {}
 
 /// Setup for the game outcomes
-#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Deserialize)]
+///
+/// Any payoff can be built directly; `Weights::new` accepts only those making a prisoner's dilemma.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize)]
 pub struct Weights {
     pub defect_defect: usize,
     pub defect_collab: (usize, usize),
     pub collab_collab: usize,
 }
 impl Weights {
+    /// Create weights, checking that they make a prisoner's dilemma
+    pub fn new(
+        defect_defect: usize,
+        defect_collab: (usize, usize),
+        collab_collab: usize,
+    ) -> Result<Self, WeightsError> {
+        let weights = Self {
+            defect_defect,
+            defect_collab,
+            collab_collab,
+        };
+        if !(defect_collab.0 > collab_collab
+            && collab_collab > defect_defect
+            && defect_defect > defect_collab.1)
+        {
+            Err(WeightsError::Unordered(weights))
+        } else if 2 * collab_collab as u128 <= defect_collab.0 as u128 + defect_collab.1 as u128 {
+            Err(WeightsError::AlternationPays(weights))
+        } else {
+            Ok(weights)
+        }
+    }
+
+    /// Check if the payoffs are ordered as `dc.0 > cc > dd > dc.1`, and `2cc > dc.0 + dc.1`
+    pub fn is_dilemma(&self) -> bool {
+        Self::new(self.defect_defect, self.defect_collab, self.collab_collab).is_ok()
+    }
+
+    /// Pure Nash equilibria of a single turn: the pairs of moves where neither player gains
+    /// by changing its own
+    pub fn nash_equilibria(&self) -> Vec<(Choice, Choice)> {
+        let moves = [Choice::Defect, Choice::Collab];
+        moves
+            .into_iter()
+            .flat_map(|ch1| moves.into_iter().map(move |ch2| (ch1, ch2)))
+            .filter(|&(ch1, ch2)| {
+                let (pts1, pts2) = self.outcome(ch1, ch2);
+                pts1 >= self.outcome(!ch1, ch2).0 && pts2 >= self.outcome(ch1, !ch2).1
+            })
+            .collect()
+    }
+
+    /// Probability of collaborating in the symmetric mixed Nash equilibrium of a single turn,
+    /// where both moves give the same expected points
+    ///
+    /// `None` if there is no such equilibrium, as in a proper dilemma, or if every mix is one.
+    pub fn mixed_nash_equilibrium(&self) -> Option<f64> {
+        let (dd, cc) = (self.defect_defect as f64, self.collab_collab as f64);
+        let (temptation, sucker) = (self.defect_collab.0 as f64, self.defect_collab.1 as f64);
+        let den = cc - sucker - temptation + dd;
+        if den == 0. {
+            return None;
+        }
+        let collab = (dd - sucker) / den;
+        (0. < collab && collab < 1.).then_some(collab)
+    }
+
     #[must_use]
     #[inline]
     const fn outcome(&self, ch1: Choice, ch2: Choice) -> (usize, usize) {
@@ -46,6 +221,84 @@ impl Weights {
             - self.defect_collab.0.min(self.defect_collab.1)
     }
 }
+/// Written as `dd,dcw-dcl,cc`, e.g. `2,3-0,1` for the default weights
+impl Display for Weights {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{},{}-{},{}",
+            self.defect_defect, self.defect_collab.0, self.defect_collab.1, self.collab_collab
+        )
+    }
+}
+impl FromStr for Weights {
+    type Err = ParseWeightsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (_, defect_defect, defect_collab_0, defect_collab_1, collab_collab, rest) =
+            regex_captures!(r"^(\d+),(\d+)\-(\d+),(\d+)(.*)$", s)
+                .ok_or(ParseWeightsError::Format)?;
+        if !rest.is_empty() {
+            return Err(ParseWeightsError::Trailing(rest.to_owned()));
+        }
+        let parse = |n: &str, field| n.parse().map_err(|_| ParseWeightsError::Overflow(field));
+        Ok(Weights {
+            defect_defect: parse(defect_defect, "dd")?,
+            defect_collab: (
+                parse(defect_collab_0, "dcw")?,
+                parse(defect_collab_1, "dcl")?,
+            ),
+            collab_collab: parse(collab_collab, "cc")?,
+        })
+    }
+}
+
+/// Error in parsing `Weights`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseWeightsError {
+    Format,
+    /// Characters after the weights
+    Trailing(String),
+    /// The named weight does not fit
+    Overflow(&'static str),
+}
+impl Display for ParseWeightsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseWeightsError::Format => {
+                write!(f, "The weights must be in the format `dd,dcw-dcl,cc`")
+            }
+            ParseWeightsError::Trailing(rest) => {
+                write!(f, "Unexpected `{rest}` after the weights")
+            }
+            ParseWeightsError::Overflow(field) => write!(f, "Integer overflow in {field}"),
+        }
+    }
+}
+impl Error for ParseWeightsError {}
+
+/// Error in creating dilemma `Weights`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WeightsError {
+    /// The payoffs are not ordered as `dc.0 > cc > dd > dc.1`
+    Unordered(Weights),
+    /// Taking turns exploiting each other pays at least as much as collaborating
+    AlternationPays(Weights),
+}
+impl Display for WeightsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WeightsError::Unordered(_) => {
+                write!(f, "The payoffs must be ordered as `dc.0 > cc > dd > dc.1`")
+            }
+            WeightsError::AlternationPays(_) => {
+                write!(f, "Collaborating must pay more than alternating defections")
+            }
+        }
+    }
+}
+impl Error for WeightsError {}
+
 impl Default for Weights {
     fn default() -> Self {
         Self {
@@ -56,8 +309,213 @@ impl Default for Weights {
     }
 }
 
+/// Probabilities of collaborating for a memory-one strategy
+///
+/// Each is keyed on the last moves, own first: `p_cd` is used after collaborating
+/// against a defection.
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+pub struct MemoryOne {
+    pub p_cc: f64,
+    pub p_cd: f64,
+    pub p_dc: f64,
+    pub p_dd: f64,
+    /// Probability of collaborating at the first move
+    pub p_open: f64,
+}
+impl MemoryOne {
+    #[must_use]
+    #[inline]
+    fn probability(&self, last: Option<(&Choice, &Choice)>) -> f64 {
+        match last {
+            None => self.p_open,
+            Some((Choice::Collab, Choice::Collab)) => self.p_cc,
+            Some((Choice::Collab, Choice::Defect)) => self.p_cd,
+            Some((Choice::Defect, Choice::Collab)) => self.p_dc,
+            Some((Choice::Defect, Choice::Defect)) => self.p_dd,
+        }
+    }
+
+    /// Zero-determinant strategy that extorts the opponent by a factor `chi`
+    ///
+    /// Over a long game the gain over mutual defection of the extortioner is `chi`
+    /// times the one of its opponent. The probabilities are feasible for any
+    /// `chi >= 1` if the weights are a dilemma (`dc.0 > cc > dd > dc.1`), with
+    /// `chi == 1` being fair; otherwise they are clamped into `[0, 1]`.
+    pub fn extortionate(weights: &Weights, chi: f64) -> Self {
+        let r = weights.collab_collab as f64;
+        let p = weights.defect_defect as f64;
+        let (t, s) = (
+            weights.defect_collab.0 as f64,
+            weights.defect_collab.1 as f64,
+        );
+        // largest scale keeping all the probabilities in [0, 1]
+        let phi = [
+            (chi - 1.) * (r - p),
+            (p - s) + chi * (t - p),
+            (t - p) + chi * (p - s),
+        ]
+        .into_iter()
+        .filter(|bound| *bound > 0.)
+        .map(|bound| 1. / bound)
+        .reduce(f64::min)
+        .unwrap_or(0.);
+        Self {
+            p_cc: (1. - phi * (chi - 1.) * (r - p)).clamp(0., 1.),
+            p_cd: (1. - phi * ((p - s) + chi * (t - p))).clamp(0., 1.),
+            p_dc: (phi * ((t - p) + chi * (p - s))).clamp(0., 1.),
+            p_dd: 0.,
+            p_open: 0.,
+        }
+    }
+}
+
+/// A move in a game. The prisoner's dilemma uses `Choice`
+pub trait Action: Copy + PartialEq + Debug {}
+impl<A> Action for A where A: Copy + PartialEq + Debug {}
+
+/// Points gained by both players in a turn, given their moves
+pub trait Payoff<A: Action> {
+    fn payoff(&self, a1: A, a2: A) -> (f64, f64);
+}
+impl Payoff<Choice> for Weights {
+    fn payoff(&self, a1: Choice, a2: Choice) -> (f64, f64) {
+        let (o1, o2) = self.outcome(a1, a2);
+        (o1 as f64, o2 as f64)
+    }
+}
+/// Any function of the two moves is a payoff, e.g. a match on a table of action pairs
+impl<A, F> Payoff<A> for F
+where
+    A: Action,
+    F: Fn(A, A) -> (f64, f64),
+{
+    fn payoff(&self, a1: A, a2: A) -> (f64, f64) {
+        self(a1, a2)
+    }
+}
+
+/// What a player knows of the match when choosing a move
+#[derive(Debug, Clone, Copy)]
+pub struct Turn<'a, A: Action = Choice> {
+    /// Own moves so far
+    pub me: &'a [A],
+    /// Moves of the opponent so far, as seen by the player
+    pub them: &'a [A],
+    /// Length of the match, if the players are told
+    pub turns: Option<usize>,
+}
+impl<'a, A: Action> Turn<'a, A> {
+    fn new(me: &'a [A], them: &'a [A], turns: Option<usize>) -> Self {
+        Self { me, them, turns }
+    }
+
+    /// Index of the turn, from 0
+    pub fn index(&self) -> usize {
+        self.me.len()
+    }
+
+    /// Turns left, this one included, if the length of the match is known
+    pub fn remaining(&self) -> Option<usize> {
+        self.turns.map(|turns| turns.saturating_sub(self.index()))
+    }
+}
+
+/// A strategy able to play a match
+pub trait Strategy<A: Action = Choice> {
+    /// Choose the next move, given the moves of both players so far
+    fn play(&mut self, turn: Turn<'_, A>, rng: &mut dyn RngCore) -> A;
+
+    /// Receive what is remembered of the opponent, before the first move
+    ///
+    /// Outside of pools with memory, the opponent is always new.
+    fn recall(&mut self, _memory: &Encounters) {}
+}
+
+/// What a player remembers of an opponent from the earlier matches
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Encounters {
+    pub matches: usize,
+    /// Collaborations of the opponent
+    pub collabs: usize,
+    /// Defections of the opponent
+    pub defections: usize,
+    /// Normalized score of the last match
+    pub last_score: f64,
+}
+impl Encounters {
+    fn record(&mut self, them: &[Choice], score: f64) {
+        let defections = them.iter().filter(|c| **c == Choice::Defect).count();
+        self.matches += 1;
+        self.collabs += them.len() - defections;
+        self.defections += defections;
+        self.last_score = score;
+    }
+}
+
+/// Play a match of any game between two strategies, returning the points and the moves of both
+pub fn play_generic<A: Action>(
+    p1: &mut dyn Strategy<A>,
+    p2: &mut dyn Strategy<A>,
+    payoff: &impl Payoff<A>,
+    turns: usize,
+    rng: &mut impl Rng,
+) -> ((f64, f64), (Vec<A>, Vec<A>)) {
+    let mut points = (0., 0.);
+    let mut hist = (Vec::with_capacity(turns), Vec::with_capacity(turns));
+    for _ in 0..turns {
+        let m1 = p1.play(Turn::new(&hist.0, &hist.1, None), rng);
+        let m2 = p2.play(Turn::new(&hist.1, &hist.0, None), rng);
+        hist.0.push(m1);
+        hist.1.push(m2);
+        let (o1, o2) = payoff.payoff(m1, m2);
+        points.0 += o1;
+        points.1 += o2;
+    }
+    (points, hist)
+}
+
+/// Generator of a custom strategy
+type StrategyGen = dyn Fn(&Weights, &mut dyn RngCore) -> Box<dyn Strategy> + Send + Sync;
+
+/// A type of player defined outside of the crate
+#[derive(Clone)]
+pub struct CustomFactory {
+    pub name: String,
+    pub description: String,
+    gen: Arc<StrategyGen>,
+}
+impl CustomFactory {
+    pub fn new(
+        name: impl Into<String>,
+        description: impl Into<String>,
+        gen: impl Fn(&Weights, &mut dyn RngCore) -> Box<dyn Strategy> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            description: description.into(),
+            gen: Arc::new(gen),
+        }
+    }
+}
+/// Custom factories are equal only if they are copies of the same one
+impl PartialEq for CustomFactory {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.gen, &other.gen)
+            && self.name == other.name
+            && self.description == other.description
+    }
+}
+impl Debug for CustomFactory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CustomFactory")
+            .field("name", &self.name)
+            .field("description", &self.description)
+            .finish_non_exhaustive()
+    }
+}
+
 /// A type of player
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum PlayerFactory {
     Defector,
     Collaborator,
@@ -65,26 +523,129 @@ pub enum PlayerFactory {
     RandomFixed(f64),
     TitForTat,
     TitFotTatS,
+    GenerousTitForTat(f64),
     Mean,
     Pavlov,
     Grim,
+    TitForTwoTats,
+    TwoTitsForTat,
+    Joss(f64),
+    Gradual,
+    Prober,
+    SoftMajority,
+    HardMajority,
+    ForgivingGrim(usize),
+    ContriteTitForTat,
+    Reactive {
+        y: f64,
+        p: f64,
+        q: f64,
+    },
+    MemoryOne(MemoryOne),
+    Extortionate(f64),
+    FirmButFair,
+    Periodic(Vec<Choice>),
+    Alternator,
+    AdaptivePavlov,
+    QLearner {
+        /// Learning rate
+        alpha: f64,
+        /// Discount of the future rewards
+        gamma: f64,
+        /// Probability of exploring a random move
+        epsilon: f64,
+    },
+    /// Only differs from tit for tat in pools with memory
+    Exploiter,
+    /// Only differs from tit for tat when the length of the match is known
+    Backstabber {
+        /// Turns at the end where it defects
+        last_k: usize,
+    },
+    Handshake,
+    Bully,
+    OmegaTitForTat,
+    AntiPavlov,
+    /// Cannot be serialized
+    #[serde(skip)]
+    Custom(CustomFactory),
 }
 impl PlayerFactory {
-    fn gen(&self, _weights: &Weights, rng: &mut impl Rng) -> Player {
-        match self {
+    fn gen(&self, weights: &Weights, rng: &mut impl Rng) -> Box<dyn Strategy> {
+        Box::new(match self {
             PlayerFactory::Defector => Player::Defector,
             PlayerFactory::Collaborator => Player::Collaborator,
             PlayerFactory::Random(p) => Player::Random(*p),
             PlayerFactory::TitForTat => Player::TitForTat,
             PlayerFactory::TitFotTatS => Player::TitForTat2,
+            PlayerFactory::GenerousTitForTat(g) => Player::GenerousTitForTat(*g),
             PlayerFactory::RandomFixed(p) => match rng.gen_bool(*p) {
                 true => Player::Collaborator,
                 false => Player::Defector,
             },
-            PlayerFactory::Mean => Player::Mean,
+            PlayerFactory::Mean => Player::Mean(0),
             PlayerFactory::Pavlov => Player::Pavlov,
             PlayerFactory::Grim => Player::Grim(false),
-        }
+            PlayerFactory::TitForTwoTats => Player::TitForTwoTats,
+            PlayerFactory::TwoTitsForTat => Player::TwoTitsForTat(0),
+            PlayerFactory::Joss(p) => Player::Joss(*p),
+            PlayerFactory::Gradual => Player::Gradual {
+                defections: 0,
+                punish: 0,
+                calm: 0,
+            },
+            PlayerFactory::Prober => Player::Prober(false),
+            PlayerFactory::SoftMajority => Player::SoftMajority(0),
+            PlayerFactory::HardMajority => Player::HardMajority(0),
+            PlayerFactory::ForgivingGrim(limit) => Player::ForgivingGrim {
+                limit: *limit,
+                count: 0,
+            },
+            PlayerFactory::ContriteTitForTat => Player::ContriteTitForTat {
+                standing: true,
+                opponent_standing: true,
+            },
+            PlayerFactory::Reactive { y, p, q } => Player::Reactive {
+                y: *y,
+                p: *p,
+                q: *q,
+            },
+            PlayerFactory::MemoryOne(m) => Player::MemoryOne(*m),
+            PlayerFactory::Extortionate(chi) => {
+                Player::MemoryOne(MemoryOne::extortionate(weights, *chi))
+            }
+            PlayerFactory::FirmButFair => Player::FirmButFair,
+            PlayerFactory::Periodic(pattern) => Player::Periodic {
+                pattern: pattern.clone(),
+                idx: 0,
+            },
+            PlayerFactory::Alternator => Player::Periodic {
+                pattern: vec![Choice::Collab, Choice::Defect],
+                idx: 0,
+            },
+            PlayerFactory::AdaptivePavlov => Player::AdaptivePavlov(OpponentClass::Unknown),
+            PlayerFactory::QLearner {
+                alpha,
+                gamma,
+                epsilon,
+            } => Player::QLearner {
+                alpha: *alpha,
+                gamma: *gamma,
+                epsilon: *epsilon,
+                weights: *weights,
+                q: [[0.; 2]; 5],
+            },
+            PlayerFactory::Exploiter => Player::Exploiter(false),
+            PlayerFactory::Backstabber { last_k } => Player::Backstabber { last_k: *last_k },
+            PlayerFactory::Handshake => Player::Handshake(false),
+            PlayerFactory::Bully => Player::Bully,
+            PlayerFactory::OmegaTitForTat => Player::OmegaTitForTat {
+                deadlock: 0,
+                randomness: 0,
+            },
+            PlayerFactory::AntiPavlov => Player::AntiPavlov,
+            PlayerFactory::Custom(custom) => return (custom.gen)(weights, rng),
+        })
     }
     pub fn name(&self) -> Cow<'_, str> {
         match self {
@@ -93,10 +654,57 @@ impl PlayerFactory {
             PlayerFactory::Random(p) => format!("Random {:.0}%", 100. * p).into(),
             PlayerFactory::TitForTat => "TitForTat".into(),
             PlayerFactory::TitFotTatS => "TitFotTatS".into(),
+            PlayerFactory::GenerousTitForTat(g) => {
+                format!("GenerousTitForTat {:.0}%", 100. * g).into()
+            }
             PlayerFactory::RandomFixed(p) => format!("RandomFixed {:.0}%", 100. * p).into(),
             PlayerFactory::Mean => "Mean    ".into(),
             PlayerFactory::Pavlov => "Pavlov  ".into(),
             PlayerFactory::Grim => "Grim    ".into(),
+            PlayerFactory::TitForTwoTats => "TitForTwoTats".into(),
+            PlayerFactory::TwoTitsForTat => "TwoTitsForTat".into(),
+            PlayerFactory::Joss(p) => format!("Joss {:.0}%", 100. * p).into(),
+            PlayerFactory::Gradual => "Gradual ".into(),
+            PlayerFactory::Prober => "Prober  ".into(),
+            PlayerFactory::SoftMajority => "SoftMajority".into(),
+            PlayerFactory::HardMajority => "HardMajority".into(),
+            PlayerFactory::ForgivingGrim(limit) => format!("ForgivingGrim {limit}").into(),
+            PlayerFactory::ContriteTitForTat => "ContriteTitForTat".into(),
+            PlayerFactory::Reactive { y, p, q } => format!(
+                "Reactive {:.0}%,{:.0}%,{:.0}%",
+                100. * y,
+                100. * p,
+                100. * q
+            )
+            .into(),
+            PlayerFactory::MemoryOne(m) => format!(
+                "MemoryOne {:.0}%,{:.0}%,{:.0}%,{:.0}%,{:.0}%",
+                100. * m.p_cc,
+                100. * m.p_cd,
+                100. * m.p_dc,
+                100. * m.p_dd,
+                100. * m.p_open
+            )
+            .into(),
+            PlayerFactory::Extortionate(chi) => format!("Extortionate {chi}").into(),
+            PlayerFactory::FirmButFair => "FirmButFair".into(),
+            PlayerFactory::Periodic(pattern) => format!(
+                "Periodic {}",
+                pattern.iter().copied().map(char::from).collect::<String>()
+            )
+            .into(),
+            PlayerFactory::Alternator => "Alternator".into(),
+            PlayerFactory::AdaptivePavlov => "AdaptivePavlov".into(),
+            PlayerFactory::QLearner { epsilon, .. } => {
+                format!("QLearner {:.0}%", 100. * epsilon).into()
+            }
+            PlayerFactory::Exploiter => "Exploiter".into(),
+            PlayerFactory::Backstabber { last_k } => format!("Backstabber {last_k}").into(),
+            PlayerFactory::Handshake => "Handshake".into(),
+            PlayerFactory::Bully => "Bully".into(),
+            PlayerFactory::OmegaTitForTat => "OmegaTitForTat".into(),
+            PlayerFactory::AntiPavlov => "AntiPavlov".into(),
+            PlayerFactory::Custom(custom) => custom.name.as_str().into(),
         }
     }
     pub fn description(&self) -> Cow<'_, str> {
@@ -106,6 +714,11 @@ impl PlayerFactory {
             PlayerFactory::Random(p) => format!("Collaborate {:.0}% of times", 100. * p).into(),
             PlayerFactory::TitForTat => "Collaborate, then answer with the last move".into(),
             PlayerFactory::TitFotTatS => "Defect, then answer with the last move".into(),
+            PlayerFactory::GenerousTitForTat(g) => format!(
+                "Collaborate, then answer with the last move, but forgive {:.0}% of defections",
+                100. * g
+            )
+            .into(),
             PlayerFactory::RandomFixed(p) => format!(
                 "Choose the move at the start (collaborate {}%), then stick with it",
                 100. * p
@@ -116,10 +729,95 @@ impl PlayerFactory {
             }
             PlayerFactory::Pavlov => "Cooperate if the opponent moved alike".into(),
             PlayerFactory::Grim => "Cooperate until defected".into(),
+            PlayerFactory::TitForTwoTats => {
+                "Collaborate, unless the opponent defected the last two moves".into()
+            }
+            PlayerFactory::TwoTitsForTat => {
+                "Collaborate, but answer each defection with two defections".into()
+            }
+            PlayerFactory::Joss(p) => format!(
+                "Collaborate, then answer with the last move, but sneakily defect {:.0}% of times",
+                100. * p
+            )
+            .into(),
+            PlayerFactory::Gradual => {
+                "Collaborate, but answer the n-th defection with n defections and two collaborations"
+                    .into()
+            }
+            PlayerFactory::Prober => {
+                "Probe with DCC, then defect if unanswered, else answer with the last move".into()
+            }
+            PlayerFactory::SoftMajority => {
+                "Collaborate if the opponent collaborated at least as much as it defected".into()
+            }
+            PlayerFactory::HardMajority => {
+                "Collaborate if the opponent collaborated more than it defected".into()
+            }
+            PlayerFactory::ForgivingGrim(limit) => {
+                format!("Cooperate until defected {limit} times").into()
+            }
+            PlayerFactory::ContriteTitForTat => {
+                "Answer with the last move, but accept retaliation for unjustified defections".into()
+            }
+            PlayerFactory::Reactive { y, p, q } => format!(
+                "Collaborate {:.0}% at first, then {:.0}% after a collaboration, {:.0}% after a defection",
+                100. * y,
+                100. * p,
+                100. * q
+            )
+            .into(),
+            PlayerFactory::MemoryOne(_) => {
+                "Collaborate with a probability depending on the last moves of both".into()
+            }
+            PlayerFactory::Extortionate(chi) => {
+                format!("Force the opponent's gain to be 1/{chi} of own gain").into()
+            }
+            PlayerFactory::FirmButFair => "Cooperate unless suckered in the last move".into(),
+            PlayerFactory::Periodic(pattern) => format!(
+                "Repeat {} regardless of the opponent",
+                pattern.iter().copied().map(char::from).collect::<String>()
+            )
+            .into(),
+            PlayerFactory::Alternator => "Alternate collaborating and defecting".into(),
+            PlayerFactory::AdaptivePavlov => {
+                "Tit for tat, but defect against opponents classified as defectors or random".into()
+            }
+            PlayerFactory::QLearner { epsilon, .. } => format!(
+                "Learn the value of each move after each outcome, exploring {:.0}% of times",
+                100. * epsilon
+            )
+            .into(),
+            PlayerFactory::Exploiter => {
+                "Tit for tat, but defect against opponents that never defected before".into()
+            }
+            PlayerFactory::Backstabber { last_k } => {
+                format!("Tit for tat, but defect in the last {last_k} turns, if known").into()
+            }
+            PlayerFactory::Handshake => format!(
+                "Open with {}, then collaborate while the opponent did the same and collaborates",
+                History(HANDSHAKE.to_vec())
+            )
+            .into(),
+            PlayerFactory::Bully => {
+                "Defect, but back off for a turn each time the opponent retaliates".into()
+            }
+            PlayerFactory::OmegaTitForTat => {
+                "Tit for tat, but break echoes by collaborating, and defect against random opponents"
+                    .into()
+            }
+            PlayerFactory::AntiPavlov => "Cooperate if the opponent moved differently".into(),
+            PlayerFactory::Custom(custom) => custom.description.as_str().into(),
         }
     }
 
-    fn all() -> impl IntoIterator<Item = Self> {
+    /// The built-in strategies, as in the default roster
+    ///
+    /// ```
+    /// for player in prisoner::PlayerFactory::all() {
+    ///     println!("{}\t{}", player.name(), player.description());
+    /// }
+    /// ```
+    pub fn all() -> impl IntoIterator<Item = Self> {
         [
             Self::Defector,
             Self::Collaborator,
@@ -131,11 +829,242 @@ impl PlayerFactory {
             Self::RandomFixed(0.1),
             Self::TitForTat,
             Self::TitFotTatS,
+            Self::GenerousTitForTat(0.1),
             Self::Mean,
             Self::Pavlov,
             Self::Grim,
+            Self::TitForTwoTats,
+            Self::TwoTitsForTat,
+            Self::Joss(0.1),
+            Self::Gradual,
+            Self::Prober,
+            Self::SoftMajority,
+            Self::HardMajority,
+            Self::ForgivingGrim(3),
+            Self::ContriteTitForTat,
+            Self::Extortionate(3.),
+            Self::FirmButFair,
+            Self::Alternator,
+            Self::AdaptivePavlov,
+            Self::QLearner {
+                alpha: 0.1,
+                gamma: 0.9,
+                epsilon: 0.1,
+            },
+            Self::Handshake,
+            Self::Bully,
+            Self::OmegaTitForTat,
+            Self::AntiPavlov,
         ]
     }
+
+    /// Check that all the parameters that are probabilities are in `[0, 1]`
+    pub fn has_valid_probabilities(&self) -> bool {
+        let probabilities = match self {
+            PlayerFactory::Random(p)
+            | PlayerFactory::RandomFixed(p)
+            | PlayerFactory::GenerousTitForTat(p)
+            | PlayerFactory::Joss(p) => vec![*p],
+            PlayerFactory::Reactive { y, p, q } => vec![*y, *p, *q],
+            PlayerFactory::MemoryOne(MemoryOne {
+                p_cc,
+                p_cd,
+                p_dc,
+                p_dd,
+                p_open,
+            }) => vec![*p_cc, *p_cd, *p_dc, *p_dd, *p_open],
+            PlayerFactory::QLearner {
+                alpha,
+                gamma,
+                epsilon,
+            } => vec![*alpha, *gamma, *epsilon],
+            _ => vec![],
+        };
+        probabilities.into_iter().all(is_probability)
+    }
+
+    /// The built-in strategies, with the random ones collaborating from 10% to 90% of times
+    pub fn all_with_params() -> impl IntoIterator<Item = Self> {
+        let tenths = (1..10).map(|i| i as f64 / 10.);
+        Self::all()
+            .into_iter()
+            .filter(|p| !matches!(p, Self::Random(_) | Self::RandomFixed(_)))
+            .chain(tenths.clone().map(Self::Random))
+            .chain(tenths.map(Self::RandomFixed))
+    }
+}
+
+/// Error in parsing a `PlayerFactory`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParsePlayerError {
+    UnknownStrategy(String),
+    InvalidParameters(String),
+}
+impl Display for ParsePlayerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParsePlayerError::UnknownStrategy(name) => write!(f, "Unknown strategy `{name}`"),
+            ParsePlayerError::InvalidParameters(s) => write!(f, "Invalid parameters in `{s}`"),
+        }
+    }
+}
+impl Error for ParsePlayerError {}
+
+/// Parse a player as `name` or `name:param,param...`, e.g. `titfortat`, `random:0.7`,
+/// `reactive:0.9,0.9,0.1` or `periodic:CDDC`. Names are case insensitive.
+///
+/// The parameters can also be separated by colons, as `reactive:0.9:0.9:0.1`.
+impl FromStr for PlayerFactory {
+    type Err = ParsePlayerError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, params) = s.split_once(':').unwrap_or((s, ""));
+        let invalid = || ParsePlayerError::InvalidParameters(s.to_owned());
+        let float = || parse_floats(params).map(|[x]| x).ok_or_else(invalid);
+        let probability = || parse_probabilities(params).map(|[p]| p).ok_or_else(invalid);
+        let simple = |player| {
+            if params.is_empty() {
+                Ok(player)
+            } else {
+                Err(invalid())
+            }
+        };
+        match name.to_lowercase().as_str() {
+            "defector" => simple(Self::Defector),
+            "collaborator" => simple(Self::Collaborator),
+            "random" => Ok(Self::Random(probability()?)),
+            "randomfixed" => Ok(Self::RandomFixed(probability()?)),
+            "titfortat" => simple(Self::TitForTat),
+            "titfottats" => simple(Self::TitFotTatS),
+            "generoustitfortat" => Ok(Self::GenerousTitForTat(probability()?)),
+            "mean" => simple(Self::Mean),
+            "pavlov" => simple(Self::Pavlov),
+            "grim" => simple(Self::Grim),
+            "titfortwotats" => simple(Self::TitForTwoTats),
+            "twotitsfortat" => simple(Self::TwoTitsForTat),
+            "joss" => Ok(Self::Joss(probability()?)),
+            "gradual" => simple(Self::Gradual),
+            "prober" => simple(Self::Prober),
+            "softmajority" => simple(Self::SoftMajority),
+            "hardmajority" => simple(Self::HardMajority),
+            "forgivinggrim" => Ok(Self::ForgivingGrim(params.parse().map_err(|_| invalid())?)),
+            "contritetitfortat" => simple(Self::ContriteTitForTat),
+            "reactive" => {
+                let [y, p, q] = parse_probabilities(params).ok_or_else(invalid)?;
+                Ok(Self::Reactive { y, p, q })
+            }
+            "memoryone" => {
+                let [p_cc, p_cd, p_dc, p_dd, p_open] =
+                    parse_probabilities(params).ok_or_else(invalid)?;
+                Ok(Self::MemoryOne(MemoryOne {
+                    p_cc,
+                    p_cd,
+                    p_dc,
+                    p_dd,
+                    p_open,
+                }))
+            }
+            "extortionate" => Ok(Self::Extortionate(float()?)),
+            "firmbutfair" => simple(Self::FirmButFair),
+            "periodic" if !params.is_empty() => Ok(Self::Periodic(
+                params.parse::<History>().map_err(|_| invalid())?.0,
+            )),
+            "periodic" => Err(invalid()),
+            "alternator" => simple(Self::Alternator),
+            "adaptivepavlov" => simple(Self::AdaptivePavlov),
+            "exploiter" => simple(Self::Exploiter),
+            "handshake" => simple(Self::Handshake),
+            "bully" => simple(Self::Bully),
+            "omegatitfortat" => simple(Self::OmegaTitForTat),
+            "antipavlov" => simple(Self::AntiPavlov),
+            "backstabber" => Ok(Self::Backstabber {
+                last_k: params.parse().map_err(|_| invalid())?,
+            }),
+            "qlearner" if params.is_empty() => Ok(Self::QLearner {
+                alpha: 0.1,
+                gamma: 0.9,
+                epsilon: 0.1,
+            }),
+            "qlearner" => {
+                let [alpha, gamma, epsilon] = parse_probabilities(params).ok_or_else(invalid)?;
+                Ok(Self::QLearner {
+                    alpha,
+                    gamma,
+                    epsilon,
+                })
+            }
+            _ => Err(ParsePlayerError::UnknownStrategy(name.to_owned())),
+        }
+    }
+}
+
+/// Parse exactly `N` comma or colon separated floats
+fn parse_floats<const N: usize>(params: &str) -> Option<[f64; N]> {
+    let floats: Vec<f64> = params
+        .split([',', ':'])
+        .map(|p| p.trim().parse().ok())
+        .collect::<Option<_>>()?;
+    floats.try_into().ok()
+}
+/// Parse exactly `N` comma or colon separated probabilities
+fn parse_probabilities<const N: usize>(params: &str) -> Option<[f64; N]> {
+    parse_floats(params).filter(|ps| ps.iter().copied().all(is_probability))
+}
+
+fn is_probability(p: f64) -> bool {
+    (0. ..=1.).contains(&p)
+}
+
+/// Alternations of the opponent after which `OmegaTitForTat` collaborates to break the echo
+const OMEGA_DEADLOCK: usize = 3;
+/// Evidence of randomness after which `OmegaTitForTat` always defects
+const OMEGA_RANDOMNESS: usize = 8;
+
+/// Opening by which `Handshake` recognizes its own kind
+///
+/// No built-in strategy opens like this, `Alternator` included.
+const HANDSHAKE: [Choice; 4] = [
+    Choice::Collab,
+    Choice::Defect,
+    Choice::Defect,
+    Choice::Collab,
+];
+
+/// Last turns over which `AdaptivePavlov` classifies the opponent, at every turn
+const CLASSIFICATION_WINDOW: usize = 6;
+
+/// Kind of opponent, as inferred by `AdaptivePavlov`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OpponentClass {
+    /// Not enough turns yet
+    Unknown,
+    /// Always collaborated
+    Cooperative,
+    /// Answered with the previous move
+    Reciprocator,
+    /// Mostly defected
+    Defector,
+    Random,
+}
+impl OpponentClass {
+    /// Classify the opponent from the last window of moves
+    fn classify(me: &[Choice], them: &[Choice]) -> Self {
+        let start = them.len().saturating_sub(CLASSIFICATION_WINDOW);
+        let window = &them[start..];
+        let defections = window.iter().filter(|c| **c == Choice::Defect).count();
+        let answers = (start.max(1)..them.len())
+            .filter(|&i| them[i] == me[i - 1])
+            .count();
+        if defections == 0 {
+            Self::Cooperative
+        } else if answers + 1 >= window.len() {
+            Self::Reciprocator
+        } else if 3 * defections >= 2 * window.len() {
+            Self::Defector
+        } else {
+            Self::Random
+        }
+    }
 }
 
 /// A player
@@ -146,30 +1075,104 @@ pub enum Player {
     Random(f64),
     TitForTat,
     TitForTat2,
-    Mean,
+    GenerousTitForTat(f64),
+    /// Collaborations of the opponent so far
+    Mean(usize),
     Pavlov,
     Grim(bool),
+    TitForTwoTats,
+    /// Number of retaliations still to be made
+    TwoTitsForTat(usize),
+    Joss(f64),
+    Gradual {
+        /// Defections of the opponent so far
+        defections: usize,
+        /// Defections still to be made
+        punish: usize,
+        /// Calming collaborations still to be made
+        calm: usize,
+    },
+    /// Whether the opponent did not answer the probe
+    Prober(bool),
+    /// Collaborations of the opponent so far
+    SoftMajority(usize),
+    /// Collaborations of the opponent so far
+    HardMajority(usize),
+    ForgivingGrim {
+        /// Defections needed to start defecting forever
+        limit: usize,
+        /// Defections of the opponent so far
+        count: usize,
+    },
+    ContriteTitForTat {
+        /// Good standing: no unjustified defection was made
+        standing: bool,
+        /// Good standing of the opponent
+        opponent_standing: bool,
+    },
+    Reactive {
+        /// Probability of collaborating at the first move
+        y: f64,
+        /// Probability of collaborating after a collaboration
+        p: f64,
+        /// Probability of collaborating after a defection
+        q: f64,
+    },
+    MemoryOne(MemoryOne),
+    FirmButFair,
+    Periodic {
+        pattern: Vec<Choice>,
+        /// Position of the next move in the pattern
+        idx: usize,
+    },
+    /// Classification of the opponent from the last window
+    AdaptivePavlov(OpponentClass),
+    QLearner {
+        alpha: f64,
+        gamma: f64,
+        epsilon: f64,
+        /// Source of the rewards
+        weights: Weights,
+        /// Value of each move, collaboration first, for the start and each last outcome
+        q: [[f64; 2]; 5],
+    },
+    /// Whether the opponent never defected in the earlier matches
+    Exploiter(bool),
+    Backstabber {
+        last_k: usize,
+    },
+    /// Whether the opponent was found to be a stranger
+    Handshake(bool),
+    Bully,
+    OmegaTitForTat {
+        /// Consecutive turns the opponent alternated its moves
+        deadlock: usize,
+        /// Evidence of the opponent playing at random
+        randomness: usize,
+    },
+    AntiPavlov,
 }
-impl Player {
-    fn play(&mut self, hist: (&[Choice], &[Choice]), rng: &mut impl Rng) -> Choice {
+impl Strategy for Player {
+    fn play(&mut self, turn: Turn<'_>, rng: &mut dyn RngCore) -> Choice {
+        let hist = (turn.me, turn.them);
         match self {
             Player::Defector => Choice::Defect,
             Player::Collaborator => Choice::Collab,
             Player::Random(p) => rng.gen_bool(*p).into(),
-            Player::TitForTat => hist.1.first().copied().unwrap_or(Choice::Collab),
-            Player::TitForTat2 => hist.1.first().copied().unwrap_or(Choice::Defect),
-            Player::Mean => {
+            Player::TitForTat => hist.1.last().copied().unwrap_or(Choice::Collab),
+            Player::TitForTat2 => hist.1.last().copied().unwrap_or(Choice::Defect),
+            Player::GenerousTitForTat(g) => match hist.1.last() {
+                Some(Choice::Defect) => rng.gen_bool(*g).into(),
+                Some(Choice::Collab) | None => Choice::Collab,
+            },
+            Player::Mean(collabs) => {
+                if let Some(Choice::Collab) = hist.1.last() {
+                    *collabs += 1;
+                }
                 let m = if hist.1.is_empty() {
                     0.5
                 } else {
-                    hist.1
-                        .iter()
-                        .map(|c| match c {
-                            Choice::Defect => 0,
-                            Choice::Collab => 1,
-                        })
-                        .sum::<usize>() as f64
-                        / hist.1.len() as f64
+                    *collabs as f64 / hist.1.len() as f64
                 };
                 rng.gen_bool(m).into()
             }
@@ -184,145 +1187,1756 @@ impl Player {
                     Choice::Collab
                 }
             }
-        }
-    }
-}
-
-/// Play a game between two types of players. Return -1 for total vicory of p1,
-fn play(
-    p1: &PlayerFactory,
-    p2: &PlayerFactory,
-    weights: &Weights,
-    turns: usize,
-    rng: &mut impl Rng,
-) -> f64 {
-    let mut points = 0;
-    let mut hist = (Vec::with_capacity(turns), Vec::with_capacity(turns));
-
-    let mut p1 = p1.gen(weights, rng);
-    let mut p2 = p2.gen(weights, rng);
-
-    for _ in 0..turns {
-        let m1 = p1.play((&hist.0, &hist.1), rng);
-        let m2 = p2.play((&hist.1, &hist.0), rng);
-        hist.0.push(m1);
-        hist.1.push(m2);
-        let (o1, o2) = weights.outcome(m1, m2);
-        points += o1 as isize - o2 as isize;
-    }
-
-    points as f64 / (weights.max_diff() * turns) as f64
-}
-
-pub struct EloPool<TD>
-where
-    TD: Distribution<usize>,
-{
-    players: Vec<(PlayerFactory, usize)>,
-    weights: Weights,
-    turn_distr: TD,
-
-    /// Approximate minimum distance of two player, where one would dominate the other
-    scale: f64,
-    /// Correction factor
-    k_factor: f64,
-}
-impl<TD> EloPool<TD>
-where
-    TD: Distribution<usize>,
-{
-    pub fn new(
-        weights: Weights,
-        turn_distr: TD,
-        starting_pts: usize,
-        scale: f64,
-        k_factor: f64,
+            Player::TitForTwoTats => match hist.1 {
+                [.., Choice::Defect, Choice::Defect] => Choice::Defect,
+                _ => Choice::Collab,
+            },
+            Player::TwoTitsForTat(pending) => {
+                if let Some(Choice::Defect) = hist.1.last() {
+                    *pending = 2;
+                }
+                if *pending > 0 {
+                    *pending -= 1;
+                    Choice::Defect
+                } else {
+                    Choice::Collab
+                }
+            }
+            Player::Joss(p) => match hist.1.last() {
+                Some(Choice::Defect) => Choice::Defect,
+                Some(Choice::Collab) | None => (!rng.gen_bool(*p)).into(),
+            },
+            Player::Gradual {
+                defections,
+                punish,
+                calm,
+            } => {
+                let defected = hist.1.last() == Some(&Choice::Defect);
+                if defected {
+                    *defections += 1;
+                }
+                if *punish > 0 {
+                    *punish -= 1;
+                    Choice::Defect
+                } else if *calm > 0 {
+                    *calm -= 1;
+                    Choice::Collab
+                } else if defected {
+                    // this turn is the first of the punishment
+                    *punish = *defections - 1;
+                    *calm = 2;
+                    Choice::Defect
+                } else {
+                    Choice::Collab
+                }
+            }
+            Player::Prober(exploit) => {
+                if let [_, Choice::Collab, Choice::Collab] = hist.1 {
+                    *exploit = true;
+                }
+                match hist.1 {
+                    [] => Choice::Defect,
+                    [_] | [_, _] => Choice::Collab,
+                    _ if *exploit => Choice::Defect,
+                    [.., last] => *last,
+                }
+            }
+            Player::SoftMajority(collabs) => {
+                if let Some(Choice::Collab) = hist.1.last() {
+                    *collabs += 1;
+                }
+                (2 * *collabs >= hist.1.len()).into()
+            }
+            Player::HardMajority(collabs) => {
+                if let Some(Choice::Collab) = hist.1.last() {
+                    *collabs += 1;
+                }
+                (2 * *collabs > hist.1.len()).into()
+            }
+            Player::ForgivingGrim { limit, count } => {
+                if let Some(Choice::Defect) = hist.1.last() {
+                    *count += 1;
+                }
+                if *count >= *limit {
+                    Choice::Defect
+                } else {
+                    Choice::Collab
+                }
+            }
+            Player::ContriteTitForTat {
+                standing,
+                opponent_standing,
+            } => {
+                if let (Some(mine), Some(their)) = (hist.0.last(), hist.1.last()) {
+                    // a defection is justified only against an opponent in bad standing
+                    (*standing, *opponent_standing) = (
+                        *mine == Choice::Collab || (*standing && !*opponent_standing),
+                        *their == Choice::Collab || (*opponent_standing && !*standing),
+                    );
+                }
+                if *standing && !*opponent_standing {
+                    Choice::Defect
+                } else {
+                    Choice::Collab
+                }
+            }
+            Player::Reactive { y, p, q } => match hist.1.last() {
+                None => rng.gen_bool(*y).into(),
+                Some(Choice::Collab) => rng.gen_bool(*p).into(),
+                Some(Choice::Defect) => rng.gen_bool(*q).into(),
+            },
+            Player::MemoryOne(m) => rng
+                .gen_bool(m.probability(hist.0.last().zip(hist.1.last())))
+                .into(),
+            Player::FirmButFair => match (hist.0.last(), hist.1.last()) {
+                (Some(Choice::Collab), Some(Choice::Defect)) => Choice::Defect,
+                _ => Choice::Collab,
+            },
+            Player::Periodic { pattern, idx } => {
+                let choice = pattern.get(*idx).copied().unwrap_or(Choice::Collab);
+                *idx = (*idx + 1) % pattern.len().max(1);
+                choice
+            }
+            Player::AdaptivePavlov(class) => {
+                if hist.1.len() >= CLASSIFICATION_WINDOW {
+                    *class = OpponentClass::classify(hist.0, hist.1);
+                }
+                match class {
+                    OpponentClass::Defector | OpponentClass::Random => Choice::Defect,
+                    OpponentClass::Unknown
+                    | OpponentClass::Cooperative
+                    | OpponentClass::Reciprocator => {
+                        hist.1.last().copied().unwrap_or(Choice::Collab)
+                    }
+                }
+            }
+            Player::QLearner {
+                alpha,
+                gamma,
+                epsilon,
+                weights,
+                q,
+            } => {
+                // state of the match after `turns` turns
+                let state = |turns: usize| match turns.checked_sub(1) {
+                    None => 0,
+                    Some(last) => {
+                        1 + 2 * (hist.0[last] == Choice::Defect) as usize
+                            + (hist.1[last] == Choice::Defect) as usize
+                    }
+                };
+                let now = state(hist.0.len());
+                if let (Some(mine), Some(their)) = (hist.0.last(), hist.1.last()) {
+                    let before = state(hist.0.len() - 1);
+                    let max = [
+                        weights.defect_defect,
+                        weights.defect_collab.0,
+                        weights.defect_collab.1,
+                        weights.collab_collab,
+                    ]
+                    .into_iter()
+                    .max()
+                    .unwrap_or(0)
+                    .max(1) as f64;
+                    let reward = weights.outcome(*mine, *their).0 as f64 / max;
+                    let action = (*mine == Choice::Defect) as usize;
+                    let future = q[now][0].max(q[now][1]);
+                    q[before][action] += *alpha * (reward + *gamma * future - q[before][action]);
+                }
+                if rng.gen_bool(*epsilon) {
+                    rng.gen_bool(0.5).into()
+                } else {
+                    (q[now][0] >= q[now][1]).into()
+                }
+            }
+            Player::Exploiter(sucker) => {
+                if let Some(Choice::Defect) = hist.1.last() {
+                    *sucker = false;
+                }
+                if *sucker {
+                    Choice::Defect
+                } else {
+                    hist.1.last().copied().unwrap_or(Choice::Collab)
+                }
+            }
+            Player::Backstabber { last_k } => match turn.remaining() {
+                Some(remaining) if remaining <= *last_k => Choice::Defect,
+                _ => hist.1.last().copied().unwrap_or(Choice::Collab),
+            },
+            Player::Handshake(stranger) => {
+                if let Some(choice) = HANDSHAKE.get(hist.0.len()) {
+                    return *choice;
+                }
+                if hist.0.len() == HANDSHAKE.len() {
+                    *stranger = !hist.1.starts_with(&HANDSHAKE);
+                } else if let Some(Choice::Defect) = hist.1.last() {
+                    *stranger = true;
+                }
+                if *stranger {
+                    Choice::Defect
+                } else {
+                    Choice::Collab
+                }
+            }
+            Player::Bully => match hist.1.last() {
+                Some(Choice::Defect) => Choice::Collab,
+                Some(Choice::Collab) | None => Choice::Defect,
+            },
+            Player::OmegaTitForTat {
+                deadlock,
+                randomness,
+            } => match (hist.0.last(), hist.1) {
+                (Some(mine), [.., before, last]) => {
+                    if *randomness >= OMEGA_RANDOMNESS {
+                        return Choice::Defect;
+                    }
+                    if before != last {
+                        *deadlock += 1;
+                    } else {
+                        *deadlock = 0;
+                    }
+                    if *deadlock >= OMEGA_DEADLOCK {
+                        *deadlock = 0;
+                        return Choice::Collab;
+                    }
+                    if (*before, *last) == (Choice::Collab, Choice::Collab) {
+                        *randomness = randomness.saturating_sub(1);
+                    }
+                    *randomness += (before != last) as usize + (mine != last) as usize;
+                    if *randomness >= OMEGA_RANDOMNESS {
+                        Choice::Defect
+                    } else {
+                        *last
+                    }
+                }
+                _ => hist.1.last().copied().unwrap_or(Choice::Collab),
+            },
+            // opens collaborating, as pavlov
+            Player::AntiPavlov => (hist.0.is_empty() || hist.0.last() != hist.1.last()).into(),
+        }
+    }
+
+    fn recall(&mut self, memory: &Encounters) {
+        if let Player::Exploiter(sucker) = self {
+            *sucker = memory.matches > 0 && memory.defections == 0;
+        }
+    }
+}
+
+/// Outcome of a match
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MatchResult {
+    /// Total points of each player
+    pub points: (usize, usize),
+    /// Normalized score, from -1 for total victory of p2 to 1 for total victory of p1
+    ///
+    /// Unlike the points, it is discounted if the rules say so.
+    pub score: f64,
+}
+
+/// Play a game between two types of players
+pub fn play_match(
+    p1: &PlayerFactory,
+    p2: &PlayerFactory,
+    weights: &Weights,
+    turns: usize,
+    rng: &mut impl Rng,
+) -> MatchResult {
+    Rules::default().play_match(p1, p2, weights, turns, rng)
+}
+
+/// Play a game between two types of players, returning also the moves of both
+pub fn play_match_with_history(
+    p1: &PlayerFactory,
+    p2: &PlayerFactory,
+    weights: &Weights,
+    turns: usize,
+    rng: &mut impl Rng,
+) -> (MatchResult, (Vec<Choice>, Vec<Choice>)) {
+    Rules::default().play_match_with_history(p1, p2, weights, turns, rng)
+}
+
+/// Rules altering how a match is played, beside the weights
+///
+/// The default rules play the match as is.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Rules {
+    /// Probability of each move being flipped before being played
+    pub execution_noise: f64,
+    /// Probability of each move being seen flipped by the opponent
+    pub perception_noise: f64,
+    /// Factor weighting each turn of the score relative to the previous one
+    pub discount: f64,
+    /// Tell the players the length of the match
+    #[serde(default)]
+    pub known_horizon: bool,
+}
+impl Default for Rules {
+    fn default() -> Self {
+        Self {
+            execution_noise: 0.,
+            perception_noise: 0.,
+            discount: 1.,
+            known_horizon: false,
+        }
+    }
+}
+impl Rules {
+    /// Play a game between two types of players with these rules
+    pub fn play_match(
+        &self,
+        p1: &PlayerFactory,
+        p2: &PlayerFactory,
+        weights: &Weights,
+        turns: usize,
+        rng: &mut impl Rng,
+    ) -> MatchResult {
+        self.play_match_with_history(p1, p2, weights, turns, rng).0
+    }
+
+    /// Play a game between two types of players with these rules, returning also the moves of both
+    pub fn play_match_with_history(
+        &self,
+        p1: &PlayerFactory,
+        p2: &PlayerFactory,
+        weights: &Weights,
+        turns: usize,
+        rng: &mut impl Rng,
+    ) -> (MatchResult, (Vec<Choice>, Vec<Choice>)) {
+        let mut buffers = MatchBuffers {
+            hist: (Vec::with_capacity(turns), Vec::with_capacity(turns)),
+            seen: Default::default(),
+        };
+        let new = Encounters::default();
+        let result = self.run_match(p1, p2, weights, turns, (&new, &new), &mut buffers, rng);
+        (result, buffers.hist)
+    }
+
+    /// Play a game, recording the moves in `buffers`, the players knowing `memory` of each other
+    #[allow(clippy::too_many_arguments)]
+    fn run_match(
+        &self,
+        p1: &PlayerFactory,
+        p2: &PlayerFactory,
+        weights: &Weights,
+        turns: usize,
+        memory: (&Encounters, &Encounters),
+        buffers: &mut MatchBuffers,
+        rng: &mut impl Rng,
+    ) -> MatchResult {
+        let mut points = (0, 0);
+        // discounted difference of points, and maximum it could reach
+        let (mut diff, mut max_diff) = (0., 0.);
+        let mut discount = 1.;
+        let MatchBuffers { hist, seen } = buffers;
+        hist.0.clear();
+        hist.1.clear();
+        seen.0.clear();
+        seen.1.clear();
+
+        let mut p1 = p1.gen(weights, rng);
+        let mut p2 = p2.gen(weights, rng);
+        p1.recall(memory.0);
+        p2.recall(memory.1);
+        let horizon = self.known_horizon.then_some(turns);
+
+        for _ in 0..turns {
+            let (m1, m2) = if self.perception_noise > 0. {
+                (
+                    p1.play(Turn::new(&hist.0, &seen.0, horizon), rng),
+                    p2.play(Turn::new(&hist.1, &seen.1, horizon), rng),
+                )
+            } else {
+                (
+                    p1.play(Turn::new(&hist.0, &hist.1, horizon), rng),
+                    p2.play(Turn::new(&hist.1, &hist.0, horizon), rng),
+                )
+            };
+            // the opponents see the moves actually played
+            let m1 = flip(m1, self.execution_noise, rng);
+            let m2 = flip(m2, self.execution_noise, rng);
+            hist.0.push(m1);
+            hist.1.push(m2);
+            if self.perception_noise > 0. {
+                seen.0.push(flip(m2, self.perception_noise, rng));
+                seen.1.push(flip(m1, self.perception_noise, rng));
+            }
+            let (o1, o2) = weights.outcome(m1, m2);
+            points.0 += o1;
+            points.1 += o2;
+            diff += discount * (o1 as f64 - o2 as f64);
+            max_diff += discount * weights.max_diff() as f64;
+            discount *= self.discount;
+        }
+
+        MatchResult {
+            points,
+            // no difference was possible (no turns, or equal `defect_collab`): a draw
+            score: if max_diff > 0. { diff / max_diff } else { 0. },
+        }
+    }
+}
+
+/// Buffers for the moves of a match, kept to avoid reallocating them
+#[derive(Debug, Default)]
+struct MatchBuffers {
+    /// Moves of both players
+    hist: (Vec<Choice>, Vec<Choice>),
+    /// Moves of the opponent as seen by each player, under perception noise
+    seen: (Vec<Choice>, Vec<Choice>),
+}
+
+/// Flip a move with probability `noise`
+fn flip(choice: Choice, noise: f64, rng: &mut impl Rng) -> Choice {
+    if noise > 0. && rng.gen_bool(noise) {
+        !choice
+    } else {
+        choice
+    }
+}
+
+/// Expected outcome of a match, given the rating difference of the players
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExpectedModel {
+    /// `tanh(d / scale)`
+    #[default]
+    Tanh,
+    /// The logistic Elo curve `1 / (1 + 10^(-d / scale))`, mapped to `[-1, 1]`
+    Logistic,
+}
+impl ExpectedModel {
+    #[must_use]
+    #[inline]
+    fn expected(&self, rating_diff: f64, scale: f64) -> f64 {
+        match self {
+            ExpectedModel::Tanh => (rating_diff / scale).tanh(),
+            ExpectedModel::Logistic => 2. / (1. + 10f64.powf(-rating_diff / scale)) - 1.,
+        }
+    }
+    /// Slope of the curve between evenly rated players
+    #[must_use]
+    #[inline]
+    fn slope(&self, scale: f64) -> f64 {
+        match self {
+            ExpectedModel::Tanh => 1. / scale,
+            ExpectedModel::Logistic => 10f64.ln() / (2. * scale),
+        }
+    }
+}
+
+/// How the correction factor decreases as a player plays more games
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KSchedule {
+    /// Never decrease
+    #[default]
+    Constant,
+    /// `k / (1 + games / halflife)`
+    Harmonic { halflife: f64 },
+    /// `k / 2^(games / halflife)`
+    Exponential { halflife: f64 },
+}
+impl KSchedule {
+    #[must_use]
+    #[inline]
+    fn k_factor(&self, k_factor: f64, games: usize) -> f64 {
+        match self {
+            KSchedule::Constant => k_factor,
+            KSchedule::Harmonic { halflife } => k_factor / (1. + games as f64 / halflife),
+            KSchedule::Exponential { halflife } => k_factor * 0.5f64.powf(games as f64 / halflife),
+        }
+    }
+}
+
+/// How the players of each match are chosen
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Matchmaking {
+    /// Any two players are equally likely to meet
+    #[default]
+    Uniform,
+    /// The opponent is chosen with a gaussian weight on the rating difference
+    Proximity { width: f64 },
+    /// Both players are chosen with weight inverse to the games they played
+    Fair,
+}
+
+/// Two different indices in `0..len`, uniformly chosen. There must be at least two
+fn uniform_pair(len: usize, rng: &mut impl Rng) -> [usize; 2] {
+    let i1 = rng.gen_range(0..len);
+    [i1, (i1 + rng.gen_range(1..len)) % len]
+}
+
+/// The Elo correction of the ratings, shared by the pools rated with it
+#[derive(Debug, Clone, Copy)]
+struct EloUpdate {
+    /// Approximate minimum distance of two player, where one would dominate the other
+    scale: f64,
+    /// Correction factor
+    k_factor: f64,
+    k_schedule: KSchedule,
+    expected_model: ExpectedModel,
+    /// Maximum absolute score of a game considered a draw
+    draw_epsilon: f64,
+}
+impl EloUpdate {
+    /// Correct the ratings and records of two players, after a game the first won by `outcome`
+    ///
+    /// Returns the largest absolute correction of the two ratings.
+    fn apply<P>(
+        &self,
+        players: &mut [(P, f64, PlayerStats)],
+        [i1, i2]: [usize; 2],
+        outcome: f64,
+    ) -> f64 {
+        let rating_diff = players[i1].1 - players[i2].1;
+        let expected = self.expected_model.expected(rating_diff, self.scale);
+        let mut max_delta: f64 = 0.;
+        for (i, outcome, expected) in [(i1, outcome, expected), (i2, -outcome, -expected)] {
+            let (_, rating, stats) = &mut players[i];
+            let delta = self.k_schedule.k_factor(self.k_factor, stats.games) * (outcome - expected);
+            *rating += delta;
+            max_delta = max_delta.max(delta.abs());
+            stats.record(outcome, self.draw_epsilon);
+        }
+        max_delta
+    }
+}
+
+/// Record of the games of a player
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct PlayerStats {
+    pub games: usize,
+    pub wins: usize,
+    pub losses: usize,
+    pub draws: usize,
+    /// Mean normalized score over all the games
+    pub mean_score: f64,
+    /// Mean normalized score over about the last `RECENT_GAMES` games
+    #[serde(default)]
+    pub recent_mean: f64,
+    /// Variance of the normalized score over about the last `RECENT_GAMES` games
+    #[serde(default)]
+    pub recent_variance: f64,
+    #[serde(default)]
+    pub behavior: BehaviorStats,
+}
+impl PlayerStats {
+    /// Record a game. Scores within `draw_epsilon` from zero are draws
+    fn record(&mut self, score: f64, draw_epsilon: f64) {
+        self.games += 1;
+        if score > draw_epsilon {
+            self.wins += 1
+        } else if score < -draw_epsilon {
+            self.losses += 1
+        } else {
+            self.draws += 1
+        }
+        self.mean_score += (score - self.mean_score) / self.games as f64;
+        // exponentially weighted, and exact for the first `RECENT_GAMES` games
+        let alpha = 1. / self.recent_games() as f64;
+        let delta = score - self.recent_mean;
+        self.recent_mean += alpha * delta;
+        self.recent_variance = (1. - alpha) * (self.recent_variance + alpha * delta * delta);
+    }
+
+    /// Number of games the recent mean and variance are effectively computed on
+    fn recent_games(&self) -> usize {
+        self.games.min(RECENT_GAMES)
+    }
+}
+
+/// Number of games remembered by the recent statistics of a player
+const RECENT_GAMES: usize = 100;
+
+/// Count of the outcomes of the turns played by a player, own move first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct BehaviorStats {
+    pub cc: usize,
+    pub cd: usize,
+    pub dc: usize,
+    pub dd: usize,
+}
+impl BehaviorStats {
+    fn record(&mut self, me: &[Choice], them: &[Choice]) {
+        for moves in me.iter().zip(them) {
+            match moves {
+                (Choice::Collab, Choice::Collab) => self.cc += 1,
+                (Choice::Collab, Choice::Defect) => self.cd += 1,
+                (Choice::Defect, Choice::Collab) => self.dc += 1,
+                (Choice::Defect, Choice::Defect) => self.dd += 1,
+            }
+        }
+    }
+
+    pub fn turns(&self) -> usize {
+        self.cc + self.cd + self.dc + self.dd
+    }
+
+    /// Fraction of the turns the player collaborated
+    pub fn cooperation_rate(&self) -> f64 {
+        (self.cc + self.cd) as f64 / self.turns().max(1) as f64
+    }
+
+    /// Fraction of the turns both players collaborated
+    pub fn mutual_cooperation_rate(&self) -> f64 {
+        self.cc as f64 / self.turns().max(1) as f64
+    }
+}
+
+/// Rating of a player, with an estimate of its uncertainty
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlayerRating {
+    pub rating: f64,
+    /// Standard error, from the spread of the recent scores. Infinite before two games
+    pub std_err: f64,
+    pub games: usize,
+}
+
+/// State of an `EloPool`, without the distribution of the turns
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PoolSnapshot {
+    pub players: Vec<(PlayerFactory, f64, PlayerStats)>,
+    pub weights: Weights,
+    pub rules: Rules,
+    pub scale: f64,
+    pub k_factor: f64,
+    pub k_schedule: KSchedule,
+    pub expected_model: ExpectedModel,
+    pub draw_epsilon: f64,
+    pub matchmaking: Matchmaking,
+    pub symmetric: bool,
+    /// Whether the players remember their opponents. The memories themselves are not saved
+    #[serde(default)]
+    pub memory: bool,
+    #[serde(default = "default_series_len")]
+    pub series_len: usize,
+}
+
+fn default_series_len() -> usize {
+    1
+}
+
+impl PoolSnapshot {
+    /// Settings of the pool, with the current players as roster and the default starting points and turns
+    pub fn config(&self) -> EloPoolConfig {
+        EloPoolConfig {
+            roster: Some(self.players.iter().map(|(p, _, _)| p.clone()).collect()),
+            weights: self.weights,
+            scale: self.scale,
+            k_factor: self.k_factor,
+            execution_noise: self.rules.execution_noise,
+            perception_noise: self.rules.perception_noise,
+            discount: self.rules.discount,
+            known_horizon: self.rules.known_horizon,
+            expected_model: self.expected_model,
+            k_schedule: self.k_schedule,
+            draw_epsilon: self.draw_epsilon,
+            matchmaking: self.matchmaking,
+            symmetric: self.symmetric,
+            memory: self.memory,
+            series_len: self.series_len,
+            ..EloPoolConfig::default()
+        }
+    }
+
+    /// Check the settings, as `EloPoolConfig::validate`
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        self.config().validate()
+    }
+}
+
+pub struct EloPool<TD>
+where
+    TD: Distribution<usize>,
+{
+    /// Players, with their rating and record
+    players: Vec<(PlayerFactory, f64, PlayerStats)>,
+    weights: Weights,
+    rules: Rules,
+    turn_distr: TD,
+
+    /// Approximate minimum distance of two player, where one would dominate the other
+    scale: f64,
+    /// Correction factor
+    k_factor: f64,
+    k_schedule: KSchedule,
+    expected_model: ExpectedModel,
+    /// Maximum absolute score of a game considered a draw
+    draw_epsilon: f64,
+    matchmaking: Matchmaking,
+    /// Play each match in both orderings, averaging the outcomes
+    symmetric: bool,
+    /// Games played for each rating update, averaging the outcomes
+    series_len: usize,
+    buffers: MatchBuffers,
+    /// What each player remembers of each opponent, keyed by their indices, if enabled
+    memory: Option<HashMap<(usize, usize), Encounters>>,
+    /// Samples of the ratings, if enabled
+    rating_history: Option<RatingHistory>,
+    /// Largest corrections of the ratings in the last matches, if enabled
+    recent_deltas: Option<RecentDeltas>,
+}
+impl<TD> EloPool<TD>
+where
+    TD: Distribution<usize>,
+{
+    pub fn new(
+        weights: Weights,
+        turn_distr: TD,
+        starting_pts: f64,
+        scale: f64,
+        k_factor: f64,
+    ) -> Self {
+        Self::with_roster(
+            weights,
+            turn_distr,
+            PlayerFactory::all(),
+            starting_pts,
+            scale,
+            k_factor,
+        )
+    }
+
+    /// Create a pool with the given players, instead of all the built-in ones
+    pub fn with_roster(
+        weights: Weights,
+        turn_distr: TD,
+        roster: impl IntoIterator<Item = PlayerFactory>,
+        starting_pts: f64,
+        scale: f64,
+        k_factor: f64,
     ) -> Self {
         Self {
-            players: PlayerFactory::all()
+            players: roster
                 .into_iter()
-                .map(|p| (p, starting_pts))
+                .map(|p| (p, starting_pts, PlayerStats::default()))
                 .collect(),
             weights,
+            rules: Rules::default(),
             turn_distr,
             scale,
             k_factor,
+            k_schedule: KSchedule::default(),
+            expected_model: ExpectedModel::default(),
+            draw_epsilon: 0.05,
+            matchmaking: Matchmaking::default(),
+            symmetric: false,
+            series_len: 1,
+            buffers: MatchBuffers::default(),
+            memory: None,
+            rating_history: None,
+            recent_deltas: None,
         }
     }
 
+    /// Create a pool with `count` copies of each type of player
+    ///
+    /// Each copy is a separate player, with its own rating and record, and the copies of a
+    /// type meet each other too. Their ratings fluctuate around the same value: use
+    /// `population_ratings` to see their average.
+    pub fn with_population(
+        weights: Weights,
+        turn_distr: TD,
+        population: impl IntoIterator<Item = (PlayerFactory, usize)>,
+        starting_pts: f64,
+        scale: f64,
+        k_factor: f64,
+    ) -> Self {
+        Self::with_roster(
+            weights,
+            turn_distr,
+            population
+                .into_iter()
+                .flat_map(|(factory, count)| std::iter::repeat_n(factory, count)),
+            starting_pts,
+            scale,
+            k_factor,
+        )
+    }
+
+    /// Play the matches with the given rules
+    pub fn with_rules(mut self, rules: Rules) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    pub fn with_expected_model(mut self, expected_model: ExpectedModel) -> Self {
+        self.expected_model = expected_model;
+        self
+    }
+
+    pub fn with_k_schedule(mut self, k_schedule: KSchedule) -> Self {
+        self.k_schedule = k_schedule;
+        self
+    }
+
+    pub fn with_draw_epsilon(mut self, draw_epsilon: f64) -> Self {
+        self.draw_epsilon = draw_epsilon;
+        self
+    }
+
+    pub fn with_matchmaking(mut self, matchmaking: Matchmaking) -> Self {
+        self.matchmaking = matchmaking;
+        self
+    }
+
+    pub fn with_symmetric(mut self, symmetric: bool) -> Self {
+        self.symmetric = symmetric;
+        self
+    }
+
+    /// Update the ratings after series of `series_len` games, each with its own number of turns
+    ///
+    /// The series counts as a single game in the records.
+    pub fn with_series_len(mut self, series_len: usize) -> Self {
+        self.series_len = series_len.max(1);
+        self
+    }
+
+    /// Let the players remember their opponents across matches
+    pub fn with_memory(mut self, memory: bool) -> Self {
+        self.memory = memory.then(HashMap::new);
+        self
+    }
+
+    /// Record the ratings of all the players every `interval` matches
+    ///
+    /// If `max_len` is given only the last `max_len` samples are kept.
+    pub fn with_rating_history(mut self, interval: usize, max_len: Option<usize>) -> Self {
+        self.rating_history = Some(RatingHistory {
+            interval: interval.max(1),
+            max_len,
+            matches: 0,
+            samples: VecDeque::new(),
+        });
+        self
+    }
+
+    /// Track the largest correction of the ratings over the last `window` matches
+    pub fn with_delta_window(mut self, window: usize) -> Self {
+        self.recent_deltas = Some(RecentDeltas {
+            window: window.max(1),
+            matches: 0,
+            candidates: VecDeque::new(),
+        });
+        self
+    }
+
+    pub fn add_player(&mut self, factory: PlayerFactory, starting_pts: f64) {
+        self.players
+            .push((factory, starting_pts, PlayerStats::default()))
+    }
+
+    /// Remove the player at `index`, returning it with its rating. The memories are forgotten
+    pub fn remove_player(&mut self, index: usize) -> Option<(PlayerFactory, f64)> {
+        (index < self.players.len()).then(|| {
+            if let Some(memory) = &mut self.memory {
+                memory.clear()
+            }
+            let (factory, rating, _) = self.players.remove(index);
+            (factory, rating)
+        })
+    }
+
     pub fn play(&mut self, rng: &mut impl Rng) {
         if self.players.len() < 2 {
             return;
         }
-        let [i1, i2] = {
-            let i1 = rng.gen_range(0..self.players.len());
-            let mut i2 = rng.gen_range(0..self.players.len());
-            while i1 == i2 {
-                i2 = rng.gen_range(0..self.players.len());
+        let pair = self.pair(rng);
+        self.play_pair(pair, rng)
+    }
+
+    /// Play a match fully determined by `match_seed` and the current state of the pool
+    ///
+    /// The pairing and the match use different generators, so the outcome of a given
+    /// matchup and seed can be replayed even if the ratings changed.
+    pub fn play_seeded(&mut self, match_seed: u64) {
+        if self.players.len() < 2 {
+            return;
+        }
+        let pair = self.pair(&mut SmallRng::seed_from_u64(match_seed));
+        self.play_pair(
+            pair,
+            &mut SmallRng::seed_from_u64(match_seed ^ 0x9e37_79b9_7f4a_7c15),
+        )
+    }
+
+    /// Play a series of games between the players at the given indices, and update them
+    fn play_pair(&mut self, [i1, i2]: [usize; 2], rng: &mut impl Rng) {
+        let mut outcome = 0.;
+        for _ in 0..self.series_len {
+            let turns = self.turn_distr.sample(rng);
+            let mut game = self.remembered_match([i1, i2], turns, rng);
+            if self.symmetric {
+                let reverse = self.remembered_match([i2, i1], turns, rng);
+                game = (game - reverse) / 2.;
             }
-            [i1, i2]
+            outcome += game;
+        }
+        outcome /= self.series_len as f64;
+        let delta = EloUpdate {
+            scale: self.scale,
+            k_factor: self.k_factor,
+            k_schedule: self.k_schedule,
+            expected_model: self.expected_model,
+            draw_epsilon: self.draw_epsilon,
+        }
+        .apply(&mut self.players, [i1, i2], outcome);
+        if let Some(history) = &mut self.rating_history {
+            history.record(&self.players)
+        }
+        if let Some(recent_deltas) = &mut self.recent_deltas {
+            recent_deltas.record(delta)
+        }
+    }
+
+    /// Play a match with the memories of the players, updating them and the behaviors. Returns the score
+    fn remembered_match(&mut self, [i1, i2]: [usize; 2], turns: usize, rng: &mut impl Rng) -> f64 {
+        let recall = |i, j| {
+            self.memory
+                .as_ref()
+                .and_then(|memory| memory.get(&(i, j)))
+                .copied()
+                .unwrap_or_default()
         };
-        let outcome = play(
-            &self.players[i1].0,
-            &self.players[i2].0,
-            &self.weights,
-            self.turn_distr.sample(rng),
-            rng,
-        );
-        let rating_diff = self.players[i1].1 as f64 - self.players[i2].1 as f64;
-        let expected = (rating_diff / self.scale).tanh();
-        let correction = (self.k_factor * (outcome - expected)) as isize;
-        // correcting the players strenght
-        self.players[i1].1 = self.players[i1].1.saturating_add_signed(correction);
-        self.players[i2].1 = self.players[i2].1.saturating_add_signed(-correction);
+        let memory = (recall(i1, i2), recall(i2, i1));
+        let (p1, p2) = (&self.players[i1].0, &self.players[i2].0);
+        let score = self
+            .rules
+            .run_match(
+                p1,
+                p2,
+                &self.weights,
+                turns,
+                (&memory.0, &memory.1),
+                &mut self.buffers,
+                rng,
+            )
+            .score;
+        let (me, them) = &self.buffers.hist;
+        if let Some(memory) = &mut self.memory {
+            memory.entry((i1, i2)).or_default().record(them, score);
+            memory.entry((i2, i1)).or_default().record(me, -score);
+        }
+        self.players[i1].2.behavior.record(me, them);
+        self.players[i2].2.behavior.record(them, me);
+        score
+    }
+
+    /// Choose two different players for a match
+    fn pair(&self, rng: &mut impl Rng) -> [usize; 2] {
+        match self.matchmaking {
+            Matchmaking::Uniform => uniform_pair(self.players.len(), rng),
+            Matchmaking::Proximity { width } => {
+                let i1 = rng.gen_range(0..self.players.len());
+                let rating = self.players[i1].1;
+                let weights = self.players.iter().enumerate().map(|(i, (_, r, _))| {
+                    if i == i1 {
+                        0.
+                    } else {
+                        (-((r - rating) / width).powi(2) / 2.).exp()
+                    }
+                });
+                let i2 = match WeightedIndex::new(weights) {
+                    Ok(distr) => distr.sample(rng),
+                    // everyone is too far, fall back to any other player
+                    Err(_) => (i1 + rng.gen_range(1..self.players.len())) % self.players.len(),
+                };
+                [i1, i2]
+            }
+            Matchmaking::Fair => {
+                let weights: Vec<_> = self
+                    .players
+                    .iter()
+                    .map(|(_, _, stats)| 1. / (stats.games + 1) as f64)
+                    .collect();
+                let mut distr = WeightedIndex::new(&weights).expect("The weights are positive");
+                let i1 = distr.sample(rng);
+                distr
+                    .update_weights(&[(i1, &0.)])
+                    .expect("There are other players");
+                [i1, distr.sample(rng)]
+            }
+        }
     }
 
-    pub fn ratings(&self) -> &[(PlayerFactory, usize)] {
-        &self.players
+    pub fn snapshot(&self) -> PoolSnapshot {
+        PoolSnapshot {
+            players: self.players.clone(),
+            weights: self.weights,
+            rules: self.rules,
+            scale: self.scale,
+            k_factor: self.k_factor,
+            k_schedule: self.k_schedule,
+            expected_model: self.expected_model,
+            draw_epsilon: self.draw_epsilon,
+            matchmaking: self.matchmaking,
+            symmetric: self.symmetric,
+            memory: self.memory.is_some(),
+            series_len: self.series_len,
+        }
+    }
+
+    pub fn from_snapshot(
+        PoolSnapshot {
+            players,
+            weights,
+            rules,
+            scale,
+            k_factor,
+            k_schedule,
+            expected_model,
+            draw_epsilon,
+            matchmaking,
+            symmetric,
+            memory,
+            series_len,
+        }: PoolSnapshot,
+        turn_distr: TD,
+    ) -> Self {
+        Self {
+            players,
+            weights,
+            rules,
+            turn_distr,
+            scale,
+            k_factor,
+            k_schedule,
+            expected_model,
+            draw_epsilon,
+            matchmaking,
+            symmetric,
+            series_len: series_len.max(1),
+            buffers: MatchBuffers::default(),
+            memory: memory.then(HashMap::new),
+            rating_history: None,
+            recent_deltas: None,
+        }
+    }
+
+    /// Save the state of the pool as JSON. Custom players cannot be saved
+    ///
+    /// The state is written to a temporary file next to `path`, then moved over it, so an
+    /// interrupted save leaves the previous state.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        let mut temp = path.as_os_str().to_owned();
+        temp.push(".tmp");
+        let mut writer = BufWriter::new(File::create(&temp)?);
+        serde_json::to_writer(&mut writer, &self.snapshot())?;
+        writer.into_inner()?.sync_all()?;
+        fs::rename(temp, path)
+    }
+
+    /// Resume a pool saved with `save`, checking its settings
+    pub fn load(path: impl AsRef<Path>, turn_distr: TD) -> io::Result<Self> {
+        let snapshot: PoolSnapshot = serde_json::from_reader(BufReader::new(File::open(path)?))?;
+        snapshot
+            .validate()
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        Ok(Self::from_snapshot(snapshot, turn_distr))
+    }
+
+    pub fn ratings(&self) -> impl Iterator<Item = (&PlayerFactory, PlayerRating)> + '_ {
+        self.players.iter().map(|(p, rating, stats)| {
+            let std_err = if stats.games < 2 {
+                f64::INFINITY
+            } else {
+                // error on the recent mean score, brought back to the rating scale
+                (stats.recent_variance / (stats.recent_games() - 1) as f64).sqrt()
+                    / self.expected_model.slope(self.scale)
+            };
+            (
+                p,
+                PlayerRating {
+                    rating: *rating,
+                    std_err,
+                    games: stats.games,
+                },
+            )
+        })
+    }
+
+    /// Average rating of the copies of each type of player, with their number, in order of appearance
+    pub fn population_ratings(&self) -> Vec<(&PlayerFactory, f64, usize)> {
+        let mut population: Vec<(&PlayerFactory, f64, usize)> = Vec::new();
+        for (factory, rating, _) in &self.players {
+            match population.iter_mut().find(|(f, _, _)| *f == factory) {
+                Some((_, total, count)) => {
+                    *total += rating;
+                    *count += 1;
+                }
+                None => population.push((factory, *rating, 1)),
+            }
+        }
+        for (_, total, count) in &mut population {
+            *total /= *count as f64;
+        }
+        population
+    }
+
+    pub fn stats(&self) -> impl Iterator<Item = (&PlayerFactory, &PlayerStats)> + '_ {
+        self.players.iter().map(|(p, _, s)| (p, s))
+    }
+
+    pub fn behavior_stats(&self) -> impl Iterator<Item = (&PlayerFactory, &BehaviorStats)> + '_ {
+        self.players.iter().map(|(p, _, s)| (p, &s.behavior))
+    }
+
+    /// The recorded samples of the ratings, oldest first, each in the order of the players
+    ///
+    /// Empty unless enabled with `with_rating_history`.
+    pub fn rating_history(&self) -> impl Iterator<Item = &[f64]> + '_ {
+        self.rating_history
+            .iter()
+            .flat_map(|history| history.samples.iter().map(Vec::as_slice))
+    }
+
+    /// Largest absolute correction of a rating over the last matches
+    ///
+    /// `None` unless enabled with `with_delta_window`, and until a full window was played.
+    pub fn max_recent_delta(&self) -> Option<f64> {
+        self.recent_deltas.as_ref().and_then(RecentDeltas::max)
+    }
+}
+
+/// Ratings of the players of an `EloPool`, sampled at regular intervals
+struct RatingHistory {
+    /// Matches between two samples
+    interval: usize,
+    /// Maximum number of samples kept
+    max_len: Option<usize>,
+    /// Matches played since the history started
+    matches: usize,
+    samples: VecDeque<Vec<f64>>,
+}
+impl RatingHistory {
+    fn record(&mut self, players: &[(PlayerFactory, f64, PlayerStats)]) {
+        self.matches += 1;
+        if !self.matches.is_multiple_of(self.interval) {
+            return;
+        }
+        if self
+            .max_len
+            .is_some_and(|max_len| self.samples.len() >= max_len)
+        {
+            self.samples.pop_front();
+        }
+        if self.max_len != Some(0) {
+            self.samples
+                .push_back(players.iter().map(|(_, rating, _)| *rating).collect());
+        }
+    }
+}
+
+/// Largest corrections of the ratings of an `EloPool` over a sliding window of matches
+struct RecentDeltas {
+    /// Matches in the window
+    window: usize,
+    /// Matches played since the tracking started
+    matches: usize,
+    /// Decreasing corrections that are the largest of the window from some match on, with the
+    /// index of their match
+    candidates: VecDeque<(usize, f64)>,
+}
+impl RecentDeltas {
+    fn record(&mut self, delta: f64) {
+        while self
+            .candidates
+            .back()
+            .is_some_and(|&(_, candidate)| candidate <= delta)
+        {
+            self.candidates.pop_back();
+        }
+        self.candidates.push_back((self.matches, delta));
+        self.matches += 1;
+        while self
+            .candidates
+            .front()
+            .is_some_and(|&(index, _)| index + self.window < self.matches)
+        {
+            self.candidates.pop_front();
+        }
+    }
+
+    fn max(&self) -> Option<f64> {
+        if self.matches < self.window {
+            return None;
+        }
+        self.candidates.front().map(|&(_, delta)| delta)
     }
 }
 
-#[derive(Debug, Clone, Copy, Deserialize)]
-#[serde(default)]
+/// Settings of an `EloPool`
+///
+/// Missing fields take their default value, so a file can set only some of them, e.g. in TOML:
+///
+/// ```toml
+/// roster = ["TitForTat", "Grim", { Random = 0.5 }]
+/// weights = { defect_defect = 1, defect_collab = [5, 0], collab_collab = 3 }
+/// k_factor = 8
+/// max_turns = 500
+/// expected_model = "logistic"
+/// k_schedule = { harmonic = { halflife = 1000 } }
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct EloPoolConfig {
+    /// Players in the pool, all the built-in ones if missing
+    pub roster: Option<Vec<PlayerFactory>>,
     pub weights: Weights,
-    pub starting_pts: usize,
+    pub starting_pts: f64,
     pub scale: f64,
     pub k_factor: f64,
     pub min_turns: usize,
     pub max_turns: usize,
+    pub execution_noise: f64,
+    pub perception_noise: f64,
+    pub discount: f64,
+    /// Tell the players the length of each match
+    pub known_horizon: bool,
+    pub expected_model: ExpectedModel,
+    pub k_schedule: KSchedule,
+    pub draw_epsilon: f64,
+    pub matchmaking: Matchmaking,
+    pub symmetric: bool,
+    /// Let the players remember their opponents across matches
+    pub memory: bool,
+    /// Games played for each rating update
+    pub series_len: usize,
 }
 
 impl Default for EloPoolConfig {
     fn default() -> Self {
         Self {
+            roster: None,
             weights: Default::default(),
-            starting_pts: 700,
+            starting_pts: 700.,
             scale: 100.,
             k_factor: 16.,
             min_turns: 100,
             max_turns: 200,
+            execution_noise: 0.,
+            perception_noise: 0.,
+            discount: 1.,
+            known_horizon: false,
+            expected_model: ExpectedModel::Tanh,
+            k_schedule: KSchedule::Constant,
+            draw_epsilon: 0.05,
+            matchmaking: Matchmaking::Uniform,
+            symmetric: false,
+            memory: false,
+            series_len: 1,
         }
     }
 }
 
-impl From<EloPoolConfig> for EloPool<Uniform<usize>> {
-    fn from(
-        EloPoolConfig {
+impl EloPoolConfig {
+    /// Check that the settings can make a pool
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.scale.is_nan() || self.scale <= 0. {
+            Err(ConfigError::Scale(self.scale))
+        } else if self.k_factor.is_nan() || self.k_factor <= 0. {
+            Err(ConfigError::KFactor(self.k_factor))
+        } else if self.min_turns > self.max_turns {
+            Err(ConfigError::Turns {
+                min: self.min_turns,
+                max: self.max_turns,
+            })
+        } else if let Some(noise) = [self.execution_noise, self.perception_noise]
+            .into_iter()
+            .find(|noise| !is_probability(*noise))
+        {
+            Err(ConfigError::Noise(noise))
+        } else if self.discount.is_nan() || self.discount <= 0. || self.discount > 1. {
+            Err(ConfigError::Discount(self.discount))
+        } else if let Some(player) = self
+            .roster
+            .iter()
+            .flatten()
+            .find(|player| !player.has_valid_probabilities())
+        {
+            Err(ConfigError::Probability(
+                player.name().trim_end().to_owned(),
+            ))
+        } else if self.series_len == 0 {
+            Err(ConfigError::SeriesLen)
+        } else if let KSchedule::Harmonic { halflife } | KSchedule::Exponential { halflife } =
+            self.k_schedule
+        {
+            if halflife.is_nan() || halflife <= 0. {
+                Err(ConfigError::Halflife(halflife))
+            } else {
+                Ok(())
+            }
+        } else {
+            Ok(())
+        }
+    }
+
+    fn into_pool<TD>(self, turn_distr: TD) -> EloPool<TD>
+    where
+        TD: Distribution<usize>,
+    {
+        let EloPoolConfig {
+            roster,
             weights,
             starting_pts,
             scale,
             k_factor,
-            min_turns,
-            max_turns,
-        }: EloPoolConfig,
-    ) -> Self {
-        Self::new(
+            execution_noise,
+            perception_noise,
+            discount,
+            known_horizon,
+            expected_model,
+            k_schedule,
+            draw_epsilon,
+            matchmaking,
+            symmetric,
+            memory,
+            series_len,
+            ..
+        } = self;
+        EloPool::with_roster(
             weights,
-            Uniform::new(min_turns, max_turns + 1),
+            turn_distr,
+            roster.unwrap_or_else(|| PlayerFactory::all().into_iter().collect()),
             starting_pts,
             scale,
             k_factor,
         )
+        .with_rules(Rules {
+            execution_noise,
+            perception_noise,
+            discount,
+            known_horizon,
+        })
+        .with_expected_model(expected_model)
+        .with_k_schedule(k_schedule)
+        .with_draw_epsilon(draw_epsilon)
+        .with_matchmaking(matchmaking)
+        .with_symmetric(symmetric)
+        .with_memory(memory)
+        .with_series_len(series_len)
+    }
+}
+
+impl From<EloPoolConfig> for EloPool<Uniform<usize>> {
+    fn from(config: EloPoolConfig) -> Self {
+        let turn_distr = Uniform::new(config.min_turns, config.max_turns + 1);
+        config.into_pool(turn_distr)
+    }
+}
+
+/// Invalid setting of an `EloPoolConfig`
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigError {
+    Scale(f64),
+    KFactor(f64),
+    Turns {
+        min: usize,
+        max: usize,
+    },
+    Noise(f64),
+    Discount(f64),
+    /// A player of the roster, by name, has a probability outside of `[0, 1]`
+    Probability(String),
+    SeriesLen,
+    Halflife(f64),
+}
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigError::Scale(scale) => write!(f, "The scale must be positive, not {scale}"),
+            ConfigError::KFactor(k) => write!(f, "The correction factor must be positive, not {k}"),
+            ConfigError::Turns { min, max } => {
+                write!(f, "The minimum turns ({min}) exceed the maximum ({max})")
+            }
+            ConfigError::Noise(noise) => write!(f, "The noise must be in [0, 1], not {noise}"),
+            ConfigError::Discount(discount) => {
+                write!(f, "The discount must be in (0, 1], not {discount}")
+            }
+            ConfigError::Probability(player) => {
+                write!(f, "The probabilities of {player} must be in [0, 1]")
+            }
+            ConfigError::SeriesLen => write!(f, "The series must have at least one game"),
+            ConfigError::Halflife(halflife) => {
+                write!(f, "The halflife must be positive, not {halflife}")
+            }
+        }
+    }
+}
+impl Error for ConfigError {}
+
+/// Builder of an `EloPool`, checking the settings
+#[derive(Debug, Clone, Default)]
+pub struct EloPoolBuilder {
+    config: EloPoolConfig,
+}
+impl EloPoolBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn roster(mut self, roster: impl IntoIterator<Item = PlayerFactory>) -> Self {
+        self.config.roster = Some(roster.into_iter().collect());
+        self
+    }
+
+    /// Use `count` copies of each type of player, as in `EloPool::with_population`
+    pub fn population(
+        mut self,
+        population: impl IntoIterator<Item = (PlayerFactory, usize)>,
+    ) -> Self {
+        self.config.roster = Some(
+            population
+                .into_iter()
+                .flat_map(|(factory, count)| std::iter::repeat_n(factory, count))
+                .collect(),
+        );
+        self
+    }
+
+    pub fn weights(mut self, weights: Weights) -> Self {
+        self.config.weights = weights;
+        self
+    }
+
+    pub fn starting_pts(mut self, starting_pts: f64) -> Self {
+        self.config.starting_pts = starting_pts;
+        self
+    }
+
+    pub fn scale(mut self, scale: f64) -> Self {
+        self.config.scale = scale;
+        self
+    }
+
+    pub fn k_factor(mut self, k_factor: f64) -> Self {
+        self.config.k_factor = k_factor;
+        self
+    }
+
+    /// Play matches of a number of turns uniformly chosen between the two, included
+    pub fn turns(mut self, min_turns: usize, max_turns: usize) -> Self {
+        (self.config.min_turns, self.config.max_turns) = (min_turns, max_turns);
+        self
+    }
+
+    pub fn rules(mut self, rules: Rules) -> Self {
+        self.config.execution_noise = rules.execution_noise;
+        self.config.perception_noise = rules.perception_noise;
+        self.config.discount = rules.discount;
+        self.config.known_horizon = rules.known_horizon;
+        self
+    }
+
+    pub fn expected_model(mut self, expected_model: ExpectedModel) -> Self {
+        self.config.expected_model = expected_model;
+        self
+    }
+
+    pub fn k_schedule(mut self, k_schedule: KSchedule) -> Self {
+        self.config.k_schedule = k_schedule;
+        self
+    }
+
+    pub fn draw_epsilon(mut self, draw_epsilon: f64) -> Self {
+        self.config.draw_epsilon = draw_epsilon;
+        self
+    }
+
+    pub fn matchmaking(mut self, matchmaking: Matchmaking) -> Self {
+        self.config.matchmaking = matchmaking;
+        self
+    }
+
+    pub fn symmetric(mut self, symmetric: bool) -> Self {
+        self.config.symmetric = symmetric;
+        self
+    }
+
+    pub fn memory(mut self, memory: bool) -> Self {
+        self.config.memory = memory;
+        self
+    }
+
+    pub fn series_len(mut self, series_len: usize) -> Self {
+        self.config.series_len = series_len;
+        self
+    }
+
+    /// The checked settings
+    pub fn config(self) -> Result<EloPoolConfig, ConfigError> {
+        self.config.validate()?;
+        Ok(self.config)
+    }
+
+    pub fn build(self) -> Result<EloPool<Uniform<usize>>, ConfigError> {
+        self.config().map(EloPool::from)
+    }
+
+    /// Build the pool drawing the turns from `turn_distr`, instead of the uniform range
+    pub fn build_with<TD>(self, turn_distr: TD) -> Result<EloPool<TD>, ConfigError>
+    where
+        TD: Distribution<usize>,
+    {
+        let Self { mut config } = self;
+        // the range is not used
+        (config.min_turns, config.max_turns) = (0, 0);
+        config.validate()?;
+        Ok(config.into_pool(turn_distr))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn max_recent_delta_matches_the_history() {
+        let window = 20;
+        let mut pool = EloPool::with_roster(
+            Weights::default(),
+            Uniform::new(10, 20),
+            [
+                PlayerFactory::TitForTat,
+                PlayerFactory::Defector,
+                PlayerFactory::Collaborator,
+                PlayerFactory::Random(0.5),
+            ],
+            700.,
+            100.,
+            16.,
+        )
+        .with_rating_history(1, None)
+        .with_delta_window(window);
+        let mut rng = SmallRng::seed_from_u64(0);
+        for _ in 1..window {
+            pool.play(&mut rng);
+        }
+        assert_eq!(pool.max_recent_delta(), None);
+        pool.play(&mut rng);
+        assert!(pool.max_recent_delta().is_some());
+        for _ in 0..200 {
+            pool.play(&mut rng);
+            let history: Vec<_> = pool.rating_history().collect();
+            let expected = history[history.len() - window - 1..]
+                .windows(2)
+                .flat_map(|pair| pair[0].iter().zip(pair[1]).map(|(a, b)| (b - a).abs()))
+                .fold(0., f64::max);
+            let delta = pool.max_recent_delta().unwrap();
+            assert!((delta - expected).abs() < 1e-9, "{delta} != {expected}");
+        }
+    }
+
+    #[test]
+    fn recent_variance_forgets_old_games() {
+        let mut stats = PlayerStats::default();
+        for i in 0..1000 {
+            stats.record(if i % 2 == 0 { 1. } else { -1. }, 0.);
+        }
+        assert!((stats.recent_variance - 1.).abs() < 0.05);
+        for _ in 0..1000 {
+            stats.record(0.5, 0.);
+        }
+        assert!((stats.recent_mean - 0.5).abs() < 1e-3);
+        assert!(stats.recent_variance < 1e-3);
+        assert!(stats.mean_score > 0.2);
+    }
+
+    #[test]
+    fn player_parameters_take_both_separators() {
+        let reactive = PlayerFactory::Reactive {
+            y: 0.9,
+            p: 0.9,
+            q: 0.1,
+        };
+        assert_eq!("reactive:0.9,0.9,0.1".parse(), Ok(reactive.clone()));
+        assert_eq!("Reactive:0.9:0.9:0.1".parse(), Ok(reactive));
+        assert_eq!(
+            "reactive:0.9,0.9".parse::<PlayerFactory>(),
+            Err(ParsePlayerError::InvalidParameters(
+                "reactive:0.9,0.9".into()
+            ))
+        );
+    }
+
+    #[test]
+    fn tit_for_tat_answers_the_last_move() {
+        let weights = Weights::default();
+        let mut rng = SmallRng::seed_from_u64(0);
+        let them = [
+            Choice::Collab,
+            Choice::Defect,
+            Choice::Collab,
+            Choice::Defect,
+        ];
+        for (factory, opening) in [
+            (PlayerFactory::TitForTat, Choice::Collab),
+            (PlayerFactory::TitFotTatS, Choice::Defect),
+        ] {
+            let mut player = factory.gen(&weights, &mut rng);
+            let mut me = vec![];
+            for i in 0..=them.len() {
+                me.push(player.play(Turn::new(&me, &them[..i], None), &mut rng));
+            }
+            assert_eq!(me[0], opening);
+            assert_eq!(me[1..], them);
+        }
+    }
+
+    #[test]
+    fn adaptive_pavlov_reclassifies_every_turn() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let mut player = Player::AdaptivePavlov(OpponentClass::Unknown);
+        let them: Vec<_> = [Choice::Collab; 6]
+            .into_iter()
+            .chain([Choice::Defect; 4])
+            .collect();
+        let mut me = vec![];
+        for i in 0..=them.len() {
+            me.push(player.play(Turn::new(&me, &them[..i], None), &mut rng));
+        }
+        assert!(matches!(
+            player,
+            Player::AdaptivePavlov(OpponentClass::Defector)
+        ));
+    }
+
+    #[test]
+    fn weights_reject_trailing_input() {
+        assert_eq!(
+            "2,3-0,1garbage".parse::<Weights>(),
+            Err(ParseWeightsError::Trailing("garbage".into()))
+        );
+        assert_eq!(
+            "2,3-0,1,9".parse::<Weights>(),
+            Err(ParseWeightsError::Trailing(",9".into()))
+        );
+    }
+
+    #[test]
+    fn weights_reject_malformed_input() {
+        for s in ["", "2,3,0,1", "2,3-0", "a,3-0,1", " 2,3-0,1"] {
+            assert_eq!(
+                s.parse::<Weights>(),
+                Err(ParseWeightsError::Format),
+                "{s:?}"
+            );
+        }
+        assert_eq!(
+            "2,3-0,99999999999999999999999".parse::<Weights>(),
+            Err(ParseWeightsError::Overflow("cc"))
+        );
+    }
+
+    #[test]
+    fn weights_round_trip() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let extremes = [
+            weights(0, (0, 0), 0),
+            weights(usize::MAX, (1, usize::MAX), 7),
+        ];
+        let random = (0..1000).map(|_| {
+            let mut weight = || match rng.gen_range(0..3) {
+                0 => rng.gen_range(0..10),
+                1 => rng.gen(),
+                _ => rng.gen_range(0..1000),
+            };
+            weights(weight(), (weight(), weight()), weight())
+        });
+        for w in extremes.into_iter().chain(random) {
+            assert_eq!(w.to_string().parse(), Ok(w));
+        }
+    }
+
+    #[test]
+    fn large_weights_do_not_overflow() {
+        let max = usize::MAX;
+        assert!(Weights::new(max - 2, (max, 0), max - 1).is_ok());
+        assert_eq!(
+            Weights::new(2, (max, 1), max / 2),
+            Err(WeightsError::AlternationPays(weights(2, (max, 1), max / 2)))
+        );
+    }
+
+    #[test]
+    fn equal_defect_collab_gives_no_nan() {
+        let mut pool = EloPoolBuilder::new()
+            .weights(Weights {
+                defect_defect: 1,
+                defect_collab: (2, 2),
+                collab_collab: 3,
+            })
+            .build()
+            .unwrap();
+        let mut rng = SmallRng::seed_from_u64(0);
+        for _ in 0..1000 {
+            pool.play(&mut rng);
+        }
+        for (player, rating) in pool.ratings() {
+            assert!(
+                !rating.rating.is_nan(),
+                "{} has a NaN rating",
+                player.name()
+            );
+        }
+    }
+
+    fn weights(
+        defect_defect: usize,
+        defect_collab: (usize, usize),
+        collab_collab: usize,
+    ) -> Weights {
+        Weights {
+            defect_defect,
+            defect_collab,
+            collab_collab,
+        }
+    }
+
+    #[test]
+    fn dilemma_equilibrium() {
+        for w in [Weights::default(), weights(1, (5, 0), 3)] {
+            assert_eq!(w.nash_equilibria(), [(Choice::Defect, Choice::Defect)]);
+            assert_eq!(w.mixed_nash_equilibrium(), None);
+        }
+    }
+
+    #[test]
+    fn chicken_equilibria() {
+        let w = weights(0, (3, 1), 2);
+        assert_eq!(
+            w.nash_equilibria(),
+            [
+                (Choice::Defect, Choice::Collab),
+                (Choice::Collab, Choice::Defect)
+            ]
+        );
+        assert_eq!(w.mixed_nash_equilibrium(), Some(0.5));
+    }
+
+    #[test]
+    fn stag_hunt_equilibria() {
+        let w = weights(1, (3, 0), 5);
+        assert_eq!(
+            w.nash_equilibria(),
+            [
+                (Choice::Defect, Choice::Defect),
+                (Choice::Collab, Choice::Collab)
+            ]
+        );
+        let collab = w.mixed_nash_equilibrium().unwrap();
+        assert!((collab - 1. / 3.).abs() < 1e-12);
+    }
+
+    #[test]
+    fn harmony_equilibrium() {
+        let w = weights(1, (0, 3), 5);
+        assert_eq!(w.nash_equilibria(), [(Choice::Collab, Choice::Collab)]);
+        assert_eq!(w.mixed_nash_equilibrium(), None);
     }
 }
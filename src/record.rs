@@ -0,0 +1,67 @@
+use rand::{rngs::SmallRng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::{History, PlayerFactory, Rules, Weights};
+
+/// A match, with everything needed to replay it
+///
+/// The replay is deterministic only with the same version of `rand`, on the same platform.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MatchRecord {
+    pub weights: Weights,
+    pub rules: Rules,
+    /// Seed of the generator used in the match
+    pub seed: u64,
+    pub turns: usize,
+    pub p1: PlayerFactory,
+    pub p2: PlayerFactory,
+    /// Moves of both players
+    pub history: (History, History),
+}
+
+impl Rules {
+    /// Play a match with these rules, deterministic given the seed, and record it
+    pub fn play_match_recorded(
+        &self,
+        p1: &PlayerFactory,
+        p2: &PlayerFactory,
+        weights: &Weights,
+        turns: usize,
+        seed: u64,
+    ) -> MatchRecord {
+        let mut rng = SmallRng::seed_from_u64(seed);
+        let (_, (h1, h2)) = self.play_match_with_history(p1, p2, weights, turns, &mut rng);
+        MatchRecord {
+            weights: *weights,
+            rules: *self,
+            seed,
+            turns,
+            p1: p1.clone(),
+            p2: p2.clone(),
+            history: (h1.into(), h2.into()),
+        }
+    }
+}
+
+/// Play a match, deterministic given the seed, and record it
+pub fn play_match_recorded(
+    p1: &PlayerFactory,
+    p2: &PlayerFactory,
+    weights: &Weights,
+    turns: usize,
+    seed: u64,
+) -> MatchRecord {
+    Rules::default().play_match_recorded(p1, p2, weights, turns, seed)
+}
+
+/// Replay a recorded match, checking that the players make the same moves
+pub fn verify_record(record: &MatchRecord) -> bool {
+    let replay = record.rules.play_match_recorded(
+        &record.p1,
+        &record.p2,
+        &record.weights,
+        record.turns,
+        record.seed,
+    );
+    replay.history == record.history
+}
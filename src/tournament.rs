@@ -0,0 +1,199 @@
+use std::{cmp::Reverse, fmt::Display};
+
+use rand::Rng;
+
+use crate::{play_match, PlayerFactory, Rules, Weights};
+
+/// Runner of tournaments where every player meets all the others
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Tournament {
+    /// Rules the matches are played with
+    pub rules: Rules,
+}
+impl Tournament {
+    /// Play every ordered pair of players, self-play included, `repeats` times
+    pub fn round_robin(
+        &self,
+        factories: &[PlayerFactory],
+        weights: &Weights,
+        turns: usize,
+        repeats: usize,
+        rng: &mut impl Rng,
+    ) -> TournamentResult {
+        let mut scores = vec![0; factories.len()];
+        let mut matchups = vec![vec![0.; factories.len()]; factories.len()];
+        for (i1, p1) in factories.iter().enumerate() {
+            for (i2, p2) in factories.iter().enumerate() {
+                for _ in 0..repeats {
+                    let result = self.rules.play_match(p1, p2, weights, turns, rng);
+                    scores[i1] += result.points.0;
+                    scores[i2] += result.points.1;
+                    matchups[i1][i2] += result.score;
+                }
+                matchups[i1][i2] /= repeats.max(1) as f64;
+            }
+        }
+        TournamentResult { scores, matchups }
+    }
+}
+
+/// Outcome of a tournament
+///
+/// Players are indexed as in the roster the tournament was played with.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TournamentResult {
+    /// Total points made by each player
+    pub scores: Vec<usize>,
+    /// Average normalized score of each player, as first one, against each other
+    pub matchups: Vec<Vec<f64>>,
+}
+impl TournamentResult {
+    /// Players ordered by decreasing total points
+    pub fn ranking(&self) -> Vec<usize> {
+        let mut ranking: Vec<_> = (0..self.scores.len()).collect();
+        ranking.sort_by_key(|i| Reverse(self.scores[*i]));
+        ranking
+    }
+}
+
+/// Average normalized score of each player against each other
+///
+/// Entry `(i, j)` is the result of `factories[i]` against `factories[j]`, averaged
+/// over both orderings of the pair.
+pub fn payoff_matrix(
+    factories: &[PlayerFactory],
+    weights: &Weights,
+    turns: usize,
+    repeats: usize,
+    rng: &mut impl Rng,
+) -> Vec<Vec<f64>> {
+    let TournamentResult { matchups, .. } =
+        Tournament::default().round_robin(factories, weights, turns, repeats, rng);
+    (0..factories.len())
+        .map(|i| {
+            (0..factories.len())
+                .map(|j| (matchups[i][j] - matchups[j][i]) / 2.)
+                .collect()
+        })
+        .collect()
+}
+
+/// A payoff matrix, displayed as a table aligned by the player names
+pub struct PayoffTable<'a> {
+    pub factories: &'a [PlayerFactory],
+    pub matrix: &'a [Vec<f64>],
+}
+impl Display for PayoffTable<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let names: Vec<_> = self.factories.iter().map(PlayerFactory::name).collect();
+        let width = names.iter().map(|n| n.trim_end().len()).max().unwrap_or(0);
+        write!(f, "{:>3} {:width$}", "", "")?;
+        for j in 0..self.matrix.len() {
+            write!(f, " {j:>5}")?;
+        }
+        for (i, (name, row)) in names.iter().zip(self.matrix).enumerate() {
+            writeln!(f)?;
+            write!(f, "{i:>3} {:width$}", name.trim_end())?;
+            for score in row {
+                write!(f, " {score:>+5.2}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Ranking of the players in a round robin at each level of execution noise
+///
+/// Each player is given with its average points per turn, ordered by decreasing points.
+pub fn noise_sweep(
+    factories: &[PlayerFactory],
+    weights: &Weights,
+    turns: usize,
+    noise_levels: &[f64],
+    repeats: usize,
+    rng: &mut impl Rng,
+) -> Vec<(f64, Vec<(String, f64)>)> {
+    // each player plays both sides of every pairing
+    let played = (2 * factories.len() * repeats * turns).max(1) as f64;
+    noise_levels
+        .iter()
+        .map(|&execution_noise| {
+            let tournament = Tournament {
+                rules: Rules {
+                    execution_noise,
+                    ..Rules::default()
+                },
+            };
+            let result = tournament.round_robin(factories, weights, turns, repeats, rng);
+            let ranking = result
+                .ranking()
+                .into_iter()
+                .map(|i| {
+                    let name = factories[i].name().trim_end().to_owned();
+                    (name, result.scores[i] as f64 / played)
+                })
+                .collect();
+            (execution_noise, ranking)
+        })
+        .collect()
+}
+
+/// Candidate making the most points per turn against the opponents, equally likely to be met
+///
+/// Returns its index in `candidates` with its average points per turn.
+///
+/// # Panics
+///
+/// If `candidates` is empty.
+pub fn best_response(
+    candidates: &[PlayerFactory],
+    opponents: &[PlayerFactory],
+    weights: &Weights,
+    turns: usize,
+    repeats: usize,
+    rng: &mut impl Rng,
+) -> (usize, f64) {
+    let played = (opponents.len() * repeats * turns).max(1) as f64;
+    candidates
+        .iter()
+        .map(|candidate| {
+            let mut points = 0;
+            for opponent in opponents {
+                for _ in 0..repeats {
+                    points += play_match(candidate, opponent, weights, turns, rng)
+                        .points
+                        .0;
+                }
+            }
+            points as f64 / played
+        })
+        .enumerate()
+        .reduce(|best, other| if other.1 > best.1 { other } else { best })
+        .expect("There are no candidates")
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::SmallRng, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn defector_is_the_best_response_to_collaborator() {
+        let weights = Weights::default();
+        let (best, points) = best_response(
+            &[
+                PlayerFactory::Collaborator,
+                PlayerFactory::Defector,
+                PlayerFactory::TitForTat,
+            ],
+            &[PlayerFactory::Collaborator],
+            &weights,
+            50,
+            3,
+            &mut SmallRng::seed_from_u64(0),
+        );
+        assert_eq!(best, 1);
+        assert_eq!(points, weights.defect_collab.0 as f64);
+    }
+}